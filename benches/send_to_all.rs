@@ -0,0 +1,38 @@
+//! Times `send_to_all` over a large subscriber list against the detailed form, which
+//! still collects one result per subscriber. Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use rust_exercise_2_event::{Observable, Observer};
+
+struct Counter(u64);
+
+impl Observer<u64> for Counter {
+    fn notify(&mut self, event: &u64) {
+        self.0 += event;
+    }
+}
+
+fn time(name: &str, rounds: u32, mut f: impl FnMut()) {
+    let start = Instant::now();
+    for _ in 0..rounds {
+        f();
+    }
+    println!("{name}: {:?} per send", start.elapsed() / rounds);
+}
+
+fn main() {
+    let observers: Vec<_> = (0..10_000).map(|_| Arc::new(Mutex::new(Counter(0)))).collect();
+    let mut observable = Observable::new();
+    for observer in &observers {
+        observable.register_concrete(observer);
+    }
+    time("send_to_all", 1_000, || {
+        black_box(observable.send_to_all(black_box(&1)));
+    });
+    time("send_to_all_detailed", 1_000, || {
+        black_box(observable.send_to_all_detailed(black_box(&1)));
+    });
+}
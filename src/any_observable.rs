@@ -0,0 +1,89 @@
+use std::{any::{Any, TypeId}, collections::HashMap, sync::{Mutex, Weak}};
+
+use crate::{Observable, Observer, Subscription};
+
+/// An event bus routing events of any number of types, each to the observers
+/// registered for that exact type.
+///
+/// Internally there is one [`Observable<E>`] per event type, keyed by [`TypeId`]. A send
+/// costs a hash lookup plus a downcast on top of the usual delivery, and event types must
+/// be `'static` to have a `TypeId`.
+#[derive(Default)]
+pub struct AnyObservable {
+    observables: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl AnyObservable {
+    pub fn new() -> AnyObservable {
+        AnyObservable::default()
+    }
+    /// Registers `observer` for events of type `E`.
+    pub fn register_for<E: 'static>(&mut self, observer: Weak<Mutex<dyn Observer<E>>>) -> Subscription {
+        self.observables.entry(TypeId::of::<E>())
+            .or_insert_with(|| Box::new(Observable::<E>::new()))
+            .downcast_mut::<Observable<E>>()
+            .expect("observables are keyed by their event type")
+            .register(observer)
+    }
+    /// Sends `event` to the observers registered for `E`, returning how many were notified.
+    pub fn send<E: 'static>(&self, event: &E) -> usize {
+        self.observable::<E>().map_or(0, |o| o.send_to_all(event))
+    }
+    /// The observable holding the observers of `E`, if any were ever registered.
+    pub fn observable<E: 'static>(&self) -> Option<&Observable<E>> {
+        self.observables.get(&TypeId::of::<E>())
+            .and_then(|o| o.downcast_ref::<Observable<E>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::any_observable::*;
+    use std::sync::Arc;
+
+    struct Click(u32);
+    struct Key(char);
+
+    #[derive(Default)]
+    struct Clicks {
+        received: Vec<u32>,
+    }
+
+    impl Observer<Click> for Clicks {
+        fn notify(&mut self, event: &Click) {
+            self.received.push(event.0);
+        }
+    }
+
+    #[derive(Default)]
+    struct Keys {
+        received: Vec<char>,
+    }
+
+    impl Observer<Key> for Keys {
+        fn notify(&mut self, event: &Key) {
+            self.received.push(event.0);
+        }
+    }
+
+    #[test]
+    fn test_any_observable() {
+        let mut bus = AnyObservable::new();
+
+        let ob1: Arc<Mutex<Clicks>> = Arc::new(Mutex::new(Clicks::default()));
+        let ob1d: Arc<Mutex<dyn Observer<Click>>> = ob1.clone();
+        let ob2: Arc<Mutex<Keys>> = Arc::new(Mutex::new(Keys::default()));
+        let ob2d: Arc<Mutex<dyn Observer<Key>>> = ob2.clone();
+        bus.register_for::<Click>(Arc::downgrade(&ob1d));
+        bus.register_for::<Key>(Arc::downgrade(&ob2d));
+
+        assert_eq!(bus.send(&Click(1)), 1);
+        assert_eq!(bus.send(&Key('a')), 1);
+        assert_eq!(bus.send(&Click(2)), 1);
+        assert_eq!(bus.send(&0u8), 0);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.received.clone()), Some(vec![1, 2]));
+        assert_eq!(ob2.lock().ok().map(|v| v.received.clone()), Some(vec!['a']));
+    }
+}
@@ -0,0 +1,108 @@
+use core::cell::RefCell;
+
+use crate::Observer;
+
+/// A fixed-capacity observable that needs neither `std` nor an allocator.
+///
+/// Subscribers are borrowed `RefCell`s rather than `Arc<Mutex<_>>`, so the borrow
+/// checker replaces the weak references of [`Observable`](crate::Observable): every
+/// observer outlives the observable by construction and there are no dead entries.
+/// Registrations live in one of `N` slots, freed again by
+/// [`unregister`](Self::unregister).
+///
+/// ```
+/// use core::cell::RefCell;
+/// use rust_exercise_2_event::{observer, ArrayObservable};
+///
+/// struct Total(u32);
+/// observer!(Total: u32, |this, n| this.0 += n);
+///
+/// let total = RefCell::new(Total(0));
+/// let mut observable = ArrayObservable::<u32, 4>::new();
+/// observable.register(&total);
+/// observable.send_to_all(&5);
+/// assert_eq!(total.borrow().0, 5);
+/// ```
+pub struct ArrayObservable<'a, T, const N: usize> {
+    slots: [Option<&'a RefCell<dyn Observer<T> + 'a>>; N],
+}
+
+impl<'a, T, const N: usize> ArrayObservable<'a, T, N> {
+    pub const fn new() -> ArrayObservable<'a, T, N> {
+        ArrayObservable { slots: [None; N] }
+    }
+    /// Stores `observer` in the first free slot and returns its index, or `None` when all
+    /// `N` slots are taken.
+    pub fn register(&mut self, observer: &'a RefCell<dyn Observer<T> + 'a>) -> Option<usize> {
+        let i = self.slots.iter().position(Option::is_none)?;
+        self.slots[i] = Some(observer);
+        Some(i)
+    }
+    /// Frees slot `i`, returning whether it was occupied.
+    pub fn unregister(&mut self, i: usize) -> bool {
+        self.slots.get_mut(i).and_then(Option::take).is_some()
+    }
+    /// Number of occupied slots.
+    pub fn len(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Sends `message` to every registered observer in slot order, returning how many were
+    /// notified. An observer that is already borrowed, for instance because this is a
+    /// nested send from its own `notify`, is skipped.
+    pub fn send_to_all(&self, message: &T) -> usize {
+        self.slots.iter()
+            .flatten()
+            .filter_map(|o| o.try_borrow_mut().ok())
+            .map(|mut o| o.notify(message))
+            .count()
+    }
+}
+
+impl<T, const N: usize> Default for ArrayObservable<'_, T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::array::*;
+
+    #[derive(Default)]
+    struct Counter {
+        counter: usize,
+    }
+
+    impl Observer<usize> for Counter {
+        fn notify(&mut self, event: &usize) {
+            self.counter += event;
+        }
+    }
+
+    #[test]
+    fn test_array_observable() {
+        let ob1 = RefCell::new(Counter::default());
+        let ob2 = RefCell::new(Counter::default());
+        let ob3 = RefCell::new(Counter::default());
+        let mut observable = ArrayObservable::<usize, 2>::new();
+
+        assert_eq!(observable.register(&ob1), Some(0));
+        assert_eq!(observable.register(&ob2), Some(1));
+        assert_eq!(observable.register(&ob3), None);
+        assert_eq!(observable.send_to_all(&2), 2);
+
+        assert!(observable.unregister(0));
+        assert!(!observable.unregister(0));
+        assert_eq!(observable.register(&ob3), Some(0));
+        assert_eq!(observable.send_to_all(&3), 2);
+        assert_eq!(observable.len(), 2);
+
+        assert_eq!(ob1.borrow().counter, 2);
+        assert_eq!(ob2.borrow().counter, 5);
+        assert_eq!(ob3.borrow().counter, 3);
+    }
+}
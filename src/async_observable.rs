@@ -0,0 +1,419 @@
+use std::{
+    cell::UnsafeCell,
+    collections::BTreeSet,
+    future::Future,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    sync::{atomic::{AtomicU64, Ordering}, Mutex, MutexGuard, PoisonError, Weak},
+    task::{Context, Poll, Waker},
+};
+
+/// Future returned by [`AsyncObserver::notify`].
+pub type BoxFuture<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// Asynchronous counterpart of [`Observer`](crate::Observer).
+///
+/// `async fn` in a trait cannot be called through `dyn`, so `notify` returns a boxed
+/// future instead; implementations usually just wrap an `async move` block:
+///
+/// ```
+/// use rust_exercise_2_event::{AsyncObserver, BoxFuture};
+///
+/// struct Counter(usize);
+///
+/// impl AsyncObserver<usize> for Counter {
+///     fn notify<'a>(&'a mut self, event: &'a usize) -> BoxFuture<'a> {
+///         Box::pin(async move { self.0 += event })
+///     }
+/// }
+/// ```
+pub trait AsyncObserver<T>: Send {
+    fn notify<'a>(&'a mut self, event: &'a T) -> BoxFuture<'a>;
+}
+
+/// A mutex whose `lock` is awaited instead of blocking the thread.
+///
+/// Kept minimal so the `async` feature needs no runtime dependency; any executor
+/// can drive it.
+pub struct AsyncMutex<T: ?Sized> {
+    state: Mutex<LockState>,
+    value: UnsafeCell<T>,
+}
+
+struct LockState {
+    locked: bool,
+    waiters: Vec<Waker>,
+}
+
+// SAFETY: access to `value` is serialised by `state.locked`, exactly like `std::sync::Mutex`.
+unsafe impl<T: ?Sized + Send> Send for AsyncMutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for AsyncMutex<T> {}
+
+impl<T> AsyncMutex<T> {
+    pub fn new(value: T) -> AsyncMutex<T> {
+        AsyncMutex {
+            state: Mutex::new(LockState { locked: false, waiters: Vec::new() }),
+            value: UnsafeCell::new(value),
+        }
+    }
+}
+
+impl<T: ?Sized> AsyncMutex<T> {
+    pub fn lock(&self) -> Lock<'_, T> {
+        Lock { mutex: self }
+    }
+    pub fn try_lock(&self) -> Option<AsyncMutexGuard<'_, T>> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.locked {
+            None
+        } else {
+            state.locked = true;
+            Some(AsyncMutexGuard { mutex: self, _marker: PhantomData })
+        }
+    }
+}
+
+/// Future returned by [`AsyncMutex::lock`].
+pub struct Lock<'a, T: ?Sized> {
+    mutex: &'a AsyncMutex<T>,
+}
+
+impl<'a, T: ?Sized> Future for Lock<'a, T> {
+    type Output = AsyncMutexGuard<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.mutex.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.locked {
+            add_waiter(&mut state.waiters, cx.waker());
+            Poll::Pending
+        } else {
+            state.locked = true;
+            Poll::Ready(AsyncMutexGuard { mutex: self.mutex, _marker: PhantomData })
+        }
+    }
+}
+
+// A future polled again before being woken passes the same waker; queueing it twice
+// would wake it twice.
+fn add_waiter(waiters: &mut Vec<Waker>, waker: &Waker) {
+    if !waiters.iter().any(|w| w.will_wake(waker)) {
+        waiters.push(waker.clone());
+    }
+}
+
+pub struct AsyncMutexGuard<'a, T: ?Sized> {
+    mutex: &'a AsyncMutex<T>,
+    // the guard hands out `&T`/`&mut T`, so it is only `Sync` when `T` is
+    _marker: PhantomData<&'a mut T>,
+}
+
+impl<T: ?Sized> Deref for AsyncMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: the guard is proof that the lock is held.
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for AsyncMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the guard is proof that the lock is held.
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for AsyncMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        let waiters = {
+            let mut state = self.mutex.state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.locked = false;
+            std::mem::take(&mut state.waiters)
+        };
+        // every waiter re-polls and all but one go back to sleep; this keeps
+        // cancelled `Lock` futures from swallowing the wake-up
+        waiters.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// An [`Observable`](crate::Observable) whose observers are notified asynchronously.
+pub struct AsyncObservable<T> {
+    subscribers: Vec<AsyncEntry<T>>,
+    // next ticket handed out by `send_to_all_ordered`
+    tickets: AtomicU64,
+}
+
+struct AsyncEntry<T> {
+    observer: Weak<AsyncMutex<dyn AsyncObserver<T>>>,
+    turn: Mutex<Turn>,
+}
+
+impl<T> AsyncEntry<T> {
+    fn turn(&self) -> MutexGuard<'_, Turn> {
+        self.turn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Which ordered send may notify a subscriber next.
+struct Turn {
+    next: u64,
+    // tickets of abandoned sends that never reached this subscriber
+    skipped: BTreeSet<u64>,
+    waiters: Vec<Waker>,
+}
+
+impl Turn {
+    fn finish(&mut self, ticket: u64) {
+        if self.next != ticket {
+            self.skipped.insert(ticket);
+            return;
+        }
+        self.next += 1;
+        while self.skipped.remove(&self.next) {
+            self.next += 1;
+        }
+        self.waiters.drain(..).for_each(Waker::wake);
+    }
+}
+
+// Resolves once `ticket` is the next send allowed through to a subscriber.
+struct WaitTurn<'a> {
+    turn: &'a Mutex<Turn>,
+    ticket: u64,
+}
+
+impl Future for WaitTurn<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut turn = self.turn.lock().unwrap_or_else(PoisonError::into_inner);
+        if turn.next == self.ticket {
+            Poll::Ready(())
+        } else {
+            add_waiter(&mut turn.waiters, cx.waker());
+            Poll::Pending
+        }
+    }
+}
+
+// Gives up the turns an ordered send still holds or awaits, so that dropping its future
+// part-way does not stall the sends behind it.
+struct OrderedSend<'a, T> {
+    subscribers: &'a [AsyncEntry<T>],
+    ticket: u64,
+    position: usize,
+}
+
+impl<T> Drop for OrderedSend<'_, T> {
+    fn drop(&mut self) {
+        self.subscribers[self.position..].iter().for_each(|s| s.turn().finish(self.ticket));
+    }
+}
+
+impl<T> AsyncObservable<T> {
+    pub fn new() -> AsyncObservable<T> {
+        AsyncObservable {
+            subscribers: Vec::new(),
+            tickets: AtomicU64::new(0),
+        }
+    }
+    pub fn register(&mut self, observer: Weak<AsyncMutex<dyn AsyncObserver<T>>>) {
+        let next = *self.tickets.get_mut();
+        self.subscribers.push(AsyncEntry {
+            observer,
+            turn: Mutex::new(Turn { next, skipped: BTreeSet::new(), waiters: Vec::new() }),
+        })
+    }
+    /// Notifies every live observer in registration order, awaiting each one before the next.
+    pub async fn send_to_all(&self, message: &T) -> usize {
+        let mut delivered = 0;
+        for i in 0..self.subscribers.len() {
+            if self.send_to(message, i).await.is_some() {
+                delivered += 1;
+            }
+        }
+        delivered
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but concurrent ordered sends reach each
+    /// subscriber in the order this method was called, even when their futures are
+    /// polled in a different order.
+    ///
+    /// The order is fixed when the method is called, not when the future is first
+    /// polled. Only the order seen by each single subscriber is guaranteed: one send may
+    /// still be notifying the first subscriber while an earlier one reaches the second.
+    pub fn send_to_all_ordered<'a>(&'a self, message: &'a T) -> impl Future<Output = usize> + 'a {
+        let send = OrderedSend {
+            subscribers: &self.subscribers,
+            ticket: self.tickets.fetch_add(1, Ordering::Relaxed),
+            position: 0,
+        };
+        async move {
+            // moves the whole guard in; naming only its fields would capture copies of them
+            let mut send = send;
+            let mut delivered = 0;
+            while let Some(s) = send.subscribers.get(send.position) {
+                WaitTurn { turn: &s.turn, ticket: send.ticket }.await;
+                if let Some(observer) = s.observer.upgrade() {
+                    let mut observer = observer.lock().await;
+                    observer.notify(message).await;
+                    delivered += 1;
+                }
+                s.turn().finish(send.ticket);
+                send.position += 1;
+            }
+            delivered
+        }
+    }
+    pub async fn send_to(&self, message: &T, i: usize) -> Option<()> {
+        let observer = self.subscribers.get(i)?.observer.upgrade()?;
+        let mut observer = observer.lock().await;
+        observer.notify(message).await;
+        Some(())
+    }
+}
+
+impl<T> Default for AsyncObservable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+
+    use crate::async_observable::*;
+    use std::{sync::{mpsc, Arc}, task::Wake, thread::{self, Thread}};
+
+    struct ThreadWaker(Thread);
+
+    // reports every wake-up, so a test can tell exactly when a future was woken
+    struct ChannelWaker(mpsc::Sender<()>);
+
+    impl Wake for ChannelWaker {
+        fn wake(self: Arc<Self>) {
+            let _ = self.0.send(());
+        }
+    }
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    pub(crate) fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = std::pin::pin!(future);
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        received: Vec<usize>,
+    }
+
+    impl AsyncObserver<usize> for Recorder {
+        fn notify<'a>(&'a mut self, event: &'a usize) -> BoxFuture<'a> {
+            Box::pin(async move {
+                self.received.push(*event);
+            })
+        }
+    }
+
+    #[test]
+    fn test_async_observable() {
+        let mut observable = AsyncObservable::<usize>::new();
+
+        let ob1: Arc<AsyncMutex<Recorder>> = Arc::new(AsyncMutex::new(Recorder::default()));
+        let ob1d: Arc<AsyncMutex<dyn AsyncObserver<usize>>> = ob1.clone();
+        let ob2: Arc<AsyncMutex<Recorder>> = Arc::new(AsyncMutex::new(Recorder::default()));
+        let ob2d: Arc<AsyncMutex<dyn AsyncObserver<usize>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+
+        // the sends can be spawned onto a multi-threaded runtime
+        fn assert_send<F: Send>(future: F) -> F {
+            future
+        }
+        assert_eq!(block_on(assert_send(observable.send_to_all(&1))), 2);
+        assert_eq!(block_on(observable.send_to(&2, 1)), Some(()));
+
+        assert_eq!(ob1.try_lock().map(|v| v.received.clone()), Some(vec![1]));
+        assert_eq!(ob2.try_lock().map(|v| v.received.clone()), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_send_to_all_ordered() {
+        let mut observable = AsyncObservable::<usize>::new();
+
+        let ob1: Arc<AsyncMutex<Recorder>> = Arc::new(AsyncMutex::new(Recorder::default()));
+        let ob1d: Arc<AsyncMutex<dyn AsyncObserver<usize>>> = ob1.clone();
+        observable.register(Arc::downgrade(&ob1d));
+
+        let first = observable.send_to_all_ordered(&1);
+        let mut second = std::pin::pin!(observable.send_to_all_ordered(&2));
+        // polled first, the second send still has to wait for the first one
+        let mut cx = Context::from_waker(Waker::noop());
+        assert_eq!(second.as_mut().poll(&mut cx), Poll::Pending);
+
+        assert_eq!(block_on(first), 1);
+        assert_eq!(block_on(second), 1);
+        assert_eq!(ob1.try_lock().map(|v| v.received.clone()), Some(vec![1, 2]));
+
+        // an abandoned send gives up its turn
+        drop(observable.send_to_all_ordered(&3));
+        assert_eq!(block_on(observable.send_to_all_ordered(&4)), 1);
+        assert_eq!(ob1.try_lock().map(|v| v.received.clone()), Some(vec![1, 2, 4]));
+    }
+
+    #[test]
+    fn test_async_mutex_waits() {
+        let mutex = AsyncMutex::new(0usize);
+        let guard = mutex.try_lock().unwrap();
+        assert!(mutex.try_lock().is_none());
+
+        let (woken_tx, woken_rx) = mpsc::channel();
+        let waker = Waker::from(Arc::new(ChannelWaker(woken_tx)));
+        let mut cx = Context::from_waker(&waker);
+        let mut lock = std::pin::pin!(mutex.lock());
+        assert!(lock.as_mut().poll(&mut cx).is_pending());
+        // polled again before the wake-up, which must not queue the waker twice
+        assert!(lock.as_mut().poll(&mut cx).is_pending());
+        assert!(woken_rx.try_recv().is_err());
+
+        drop(guard);
+        assert_eq!(woken_rx.try_recv(), Ok(()));
+        assert!(woken_rx.try_recv().is_err());
+        let Poll::Ready(mut guard) = lock.as_mut().poll(&mut cx) else {
+            panic!("the lock was released");
+        };
+        *guard += 1;
+        drop(guard);
+
+        assert_eq!(mutex.try_lock().map(|v| *v), Some(1));
+    }
+
+    #[test]
+    fn test_wait_turn_dedupes_waker() {
+        let turn = Mutex::new(Turn { next: 0, skipped: BTreeSet::new(), waiters: Vec::new() });
+        let (woken_tx, woken_rx) = mpsc::channel();
+        let waker = Waker::from(Arc::new(ChannelWaker(woken_tx)));
+        let mut cx = Context::from_waker(&waker);
+
+        let mut wait = std::pin::pin!(WaitTurn { turn: &turn, ticket: 1 });
+        assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(wait.as_mut().poll(&mut cx), Poll::Pending);
+        assert_eq!(turn.lock().unwrap().waiters.len(), 1);
+
+        turn.lock().unwrap().finish(0);
+        assert_eq!(woken_rx.try_iter().count(), 1);
+        assert_eq!(wait.as_mut().poll(&mut cx), Poll::Ready(()));
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+use crate::{Observable, Observer, Subscription};
+
+/// An [`Observer`] that is told which observable it was attached to, registered with
+/// [`Observable::register_with_backref`].
+///
+/// The source is handed over as a `Weak`, so the back-reference never keeps the
+/// observable alive. It can only be locked when nobody else holds the observable's
+/// mutex, which is *not* the case inside `notify` when the send was made through that
+/// same mutex; use `try_lock` there, or keep the reference for use outside `notify`.
+pub trait SourceAware<T: ?Sized>: Observer<T> {
+    fn set_source(&mut self, source: Weak<Mutex<Observable<T>>>);
+}
+
+impl<T: ?Sized> Observable<T> {
+    /// Creates an empty observable already wrapped for sharing, as required by
+    /// [`register_with_backref`](Self::register_with_backref).
+    pub fn shared() -> Arc<Mutex<Observable<T>>> {
+        Arc::new(Mutex::new(Observable::new()))
+    }
+    /// Registers `observer` on `source` after giving it a back-reference through
+    /// [`SourceAware::set_source`].
+    ///
+    /// Only the observer's `Weak` is stored, as with [`register`](Self::register).
+    pub fn register_with_backref<O>(source: &Arc<Mutex<Observable<T>>>, observer: &Arc<Mutex<O>>) -> Subscription
+    where
+        O: SourceAware<T> + 'static,
+    {
+        observer.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .set_source(Arc::downgrade(source));
+        let observer: Arc<Mutex<dyn Observer<T>>> = observer.clone();
+        source.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .register(Arc::downgrade(&observer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::backref::*;
+
+    #[derive(Default)]
+    struct Echo {
+        source: Option<Weak<Mutex<Observable<usize>>>>,
+        received: Vec<usize>,
+    }
+
+    impl Observer<usize> for Echo {
+        fn notify(&mut self, event: &usize) {
+            self.received.push(*event);
+        }
+    }
+
+    impl SourceAware<usize> for Echo {
+        fn set_source(&mut self, source: Weak<Mutex<Observable<usize>>>) {
+            self.source = Some(source);
+        }
+    }
+
+    impl Echo {
+        fn source_live_count(&self) -> Option<usize> {
+            let source = self.source.as_ref()?.upgrade()?;
+            let count = source.lock().ok()?.live_count();
+            Some(count)
+        }
+    }
+
+    #[test]
+    fn test_register_with_backref() {
+        let source = Observable::<usize>::shared();
+
+        let ob1: Arc<Mutex<Echo>> = Arc::new(Mutex::new(Echo::default()));
+        let ob2: Arc<Mutex<Echo>> = Arc::new(Mutex::new(Echo::default()));
+        Observable::register_with_backref(&source, &ob1);
+        Observable::register_with_backref(&source, &ob2);
+
+        assert_eq!(source.lock().unwrap().send_to_all(&7), 2);
+        assert_eq!(ob1.lock().ok().map(|v| v.received.clone()), Some(vec![7]));
+        assert_eq!(ob1.lock().ok().and_then(|v| v.source_live_count()), Some(2));
+
+        drop(ob2);
+        assert_eq!(ob1.lock().ok().and_then(|v| v.source_live_count()), Some(1));
+
+        drop(source);
+        assert_eq!(ob1.lock().ok().and_then(|v| v.source_live_count()), None);
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::{Mutex, PoisonError, Weak};
+
+/// An observer that takes events in bulk, from a [`BatchingObservable`].
+pub trait BatchObserver<T>: Send {
+    /// Called once per flush with every event pushed since the previous one, oldest first.
+    fn notify_batch(&mut self, batch: &[T]);
+}
+
+/// Accumulates pushed events and delivers them together, so that bulk consumers pay for
+/// one dispatch per batch instead of one per event.
+///
+/// Batches go out on [`flush_batch`](Self::flush_batch), or as soon as they reach the
+/// size set with [`with_max_batch`](Self::with_max_batch).
+pub struct BatchingObservable<T> {
+    observers: Vec<Weak<Mutex<dyn BatchObserver<T>>>>,
+    // behind a lock so that `flush_batch` can drain it through `&self`
+    pending: Mutex<Vec<T>>,
+    // 0 never flushes on its own
+    max_batch: usize,
+}
+
+impl<T> BatchingObservable<T> {
+    pub fn new() -> BatchingObservable<T> {
+        BatchingObservable { observers: Vec::new(), pending: Mutex::new(Vec::new()), max_batch: 0 }
+    }
+    /// Flushes automatically whenever `size` events are pending. 0, the default, leaves
+    /// every flush to the caller.
+    pub fn with_max_batch(mut self, size: usize) -> BatchingObservable<T> {
+        self.max_batch = size;
+        self
+    }
+    /// Registers `observer` for the following batches. Subscribers that have been dropped
+    /// are forgotten at this point.
+    pub fn register(&mut self, observer: Weak<Mutex<dyn BatchObserver<T>>>) {
+        self.observers.retain(|o| o.strong_count() > 0);
+        self.observers.push(observer);
+    }
+    /// Adds `message` to the pending batch. Returns how many subscribers were notified if
+    /// this filled the batch and flushed it, 0 otherwise.
+    pub fn push(&mut self, message: T) -> usize {
+        let pending = self.pending.get_mut().unwrap_or_else(PoisonError::into_inner);
+        pending.push(message);
+        if self.max_batch > 0 && pending.len() >= self.max_batch {
+            self.flush_batch()
+        } else {
+            0
+        }
+    }
+    /// Number of events waiting for the next flush.
+    pub fn pending(&self) -> usize {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+    /// Delivers every pending event as one slice to each live subscriber, returning how
+    /// many were notified. Nothing is sent when no event is pending. A subscriber whose
+    /// mutex is poisoned, or already held, for instance by a flush from its own
+    /// `notify_batch`, misses the batch.
+    pub fn flush_batch(&self) -> usize {
+        let batch = std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        if batch.is_empty() {
+            return 0;
+        }
+        self.observers.iter()
+            .filter_map(Weak::upgrade)
+            .filter(|o| o.try_lock().map(|mut o| o.notify_batch(&batch)).is_ok())
+            .count()
+    }
+    /// Number of registered subscribers, dead or alive.
+    pub fn len(&self) -> usize {
+        self.observers.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+}
+
+impl<T> Default for BatchingObservable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::batching::*;
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct Batches(Vec<Vec<u32>>);
+
+    impl BatchObserver<u32> for Batches {
+        fn notify_batch(&mut self, batch: &[u32]) {
+            self.0.push(batch.to_vec());
+        }
+    }
+
+    #[test]
+    fn test_batching_observable() {
+        let mut observable = BatchingObservable::new();
+        let batches = Arc::new(Mutex::new(Batches::default()));
+        let weak: Weak<Mutex<Batches>> = Arc::downgrade(&batches);
+        observable.register(weak);
+
+        assert_eq!(observable.flush_batch(), 0);
+        for i in 1..=3 {
+            assert_eq!(observable.push(i), 0);
+        }
+        assert_eq!(observable.pending(), 3);
+        assert!(batches.lock().unwrap().0.is_empty());
+        assert_eq!(observable.flush_batch(), 1);
+        assert_eq!(observable.flush_batch(), 0);
+        assert_eq!(batches.lock().unwrap().0, vec![vec![1, 2, 3]]);
+    }
+
+    #[test]
+    fn test_batching_max_batch() {
+        let mut observable = BatchingObservable::new().with_max_batch(2);
+        let batches = Arc::new(Mutex::new(Batches::default()));
+        let weak: Weak<Mutex<Batches>> = Arc::downgrade(&batches);
+        observable.register(weak);
+
+        assert_eq!(observable.push(1), 0);
+        assert_eq!(observable.push(2), 1);
+        assert_eq!(observable.push(3), 0);
+        assert_eq!(observable.pending(), 1);
+        assert_eq!(batches.lock().unwrap().0, vec![vec![1, 2]]);
+    }
+}
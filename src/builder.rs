@@ -0,0 +1,181 @@
+use std::marker::PhantomData;
+
+use crate::{Observable, ReplayObservable};
+
+/// Marker for an [`ObservableBuilder`] that builds a plain [`Observable`].
+pub struct NoReplay;
+
+/// Marker for an [`ObservableBuilder`] that builds a [`ReplayObservable`]; set by
+/// [`ObservableBuilder::replay_last`].
+pub struct Replay(usize);
+
+/// Configures an [`Observable`] before creating it, see [`Observable::builder`].
+///
+/// What gets built is tracked in the type: only builders for `T: Clone` offer
+/// [`replay_last`](Self::replay_last), which switches [`build`](Self::build) to return a
+/// [`ReplayObservable`]. [`Observable::new`] remains the zero-configuration path; unlike
+/// it, a built observable prunes dead subscribers every 64 sends unless told otherwise
+/// with [`auto_prune_after`](Self::auto_prune_after).
+///
+/// ```
+/// use rust_exercise_2_event::Observable;
+///
+/// let observable = Observable::<String>::builder()
+///     .capacity(8)
+///     .auto_prune_threshold(0.5)
+///     .replay_last(4)
+///     .build();
+/// assert_eq!(observable.observable().len(), 0);
+/// ```
+pub struct ObservableBuilder<T: ?Sized, R = NoReplay> {
+    capacity: Option<usize>,
+    auto_prune: Option<f64>,
+    prune_every: usize,
+    replay: R,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: ?Sized> ObservableBuilder<T> {
+    pub fn new() -> ObservableBuilder<T> {
+        ObservableBuilder {
+            capacity: None,
+            auto_prune: None,
+            prune_every: 64,
+            replay: NoReplay,
+            _marker: PhantomData,
+        }
+    }
+    pub fn build(self) -> Observable<T> {
+        Observable {
+            capacity: self.capacity,
+            auto_prune: self.auto_prune,
+            prune_every: self.prune_every,
+            ..Observable::new()
+        }
+    }
+}
+
+impl<T: Clone> ObservableBuilder<T> {
+    /// Builds a [`ReplayObservable`] remembering the last `n` messages instead.
+    pub fn replay_last(self, n: usize) -> ObservableBuilder<T, Replay> {
+        ObservableBuilder {
+            capacity: self.capacity,
+            auto_prune: self.auto_prune,
+            prune_every: self.prune_every,
+            replay: Replay(n),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone> ObservableBuilder<T, Replay> {
+    pub fn build(self) -> ReplayObservable<T> {
+        let observable = ObservableBuilder {
+            capacity: self.capacity,
+            auto_prune: self.auto_prune,
+            prune_every: self.prune_every,
+            replay: NoReplay,
+            _marker: PhantomData,
+        };
+        ReplayObservable::with_observable(observable.build(), self.replay.0)
+    }
+}
+
+impl<T: ?Sized, R> ObservableBuilder<T, R> {
+    /// Caps the live subscribers accepted by [`Observable::try_register`].
+    pub fn capacity(self, max: usize) -> ObservableBuilder<T, R> {
+        ObservableBuilder { capacity: Some(max), ..self }
+    }
+    /// Prunes dead subscribers on registration whenever more than `ratio` (between 0 and
+    /// 1) of the stored entries are dead.
+    pub fn auto_prune_threshold(self, ratio: f64) -> ObservableBuilder<T, R> {
+        ObservableBuilder { auto_prune: Some(ratio), ..self }
+    }
+    /// Prunes dead subscribers once every `sends` calls to `send_to_all`, see
+    /// [`Observable::set_auto_prune_after`]. Built observables default to every 64
+    /// sends; 0 turns this off.
+    pub fn auto_prune_after(self, sends: usize) -> ObservableBuilder<T, R> {
+        ObservableBuilder { prune_every: sends, ..self }
+    }
+}
+
+impl<T: ?Sized> Default for ObservableBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> Observable<T> {
+    pub fn builder() -> ObservableBuilder<T> {
+        ObservableBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::builder::*;
+    use crate::RegisterError;
+    use crate::test_util::{received, recorder};
+
+    #[test]
+    fn test_builder() {
+        let mut observable = Observable::<usize>::builder()
+            .capacity(2)
+            .auto_prune_threshold(0.5)
+            .replay_last(1)
+            .build();
+        observable.send_to_all(&1);
+        observable.send_to_all(&2);
+
+        let (ob1, ob1w) = recorder();
+        observable.register(ob1w);
+        assert_eq!(received(&ob1), vec![2]);
+
+        let (_ob2, ob2w) = recorder();
+        let (_ob3, ob3w) = recorder();
+        assert!(observable.observable_mut().try_register(ob2w).is_ok());
+        assert_eq!(observable.observable_mut().try_register(ob3w).err(), Some(RegisterError::Full { capacity: 2, live: 2 }));
+    }
+
+    #[test]
+    fn test_auto_prune_threshold() {
+        let mut observable = Observable::<usize>::builder().auto_prune_threshold(0.5).build();
+
+        let (ob1, ob1w) = recorder::<usize>();
+        let (ob2, ob2w) = recorder::<usize>();
+        observable.register(ob1w.clone());
+        observable.register(ob2w);
+        drop(ob2);
+
+        // exactly half the entries are dead, which does not exceed the threshold
+        observable.register(ob1w);
+        assert_eq!(observable.len(), 3);
+
+        drop(ob1);
+        let (_ob3, ob3w) = recorder();
+        observable.register(ob3w);
+        assert_eq!(observable.len(), 1);
+    }
+
+    #[test]
+    fn test_auto_prune_after_default() {
+        let mut observable = Observable::<usize>::builder().build();
+        let mut never = Observable::<usize>::builder().auto_prune_after(0).build();
+
+        let (ob1, ob1w) = recorder::<usize>();
+        observable.register(ob1w.clone());
+        never.register(ob1w);
+        drop(ob1);
+        for n in 0..64 {
+            observable.send_to_all(&n);
+            never.send_to_all(&n);
+        }
+
+        let (_ob2, ob2w) = recorder();
+        observable.register(ob2w.clone());
+        never.register(ob2w);
+        assert_eq!(observable.len(), 1);
+        assert_eq!(never.len(), 2);
+    }
+}
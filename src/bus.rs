@@ -0,0 +1,115 @@
+use std::sync::{Mutex, Weak};
+
+use crate::{Observable, Observer, Subscription};
+
+/// A ready-made publish/subscribe hub for one event type, usually an enum of everything
+/// that can happen.
+///
+/// Handlers are closures owned by the bus, so nothing has to be kept alive on the
+/// subscriber's side. Observers registered with
+/// [`subscribe_observer`](Self::subscribe_observer) are held weakly as usual, and the
+/// entries left behind once they are dropped are pruned as new subscribers arrive. The
+/// underlying [`Observable`] stays reachable for everything the bus does not wrap.
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use rust_exercise_2_event::EventBus;
+///
+/// enum Event {
+///     Login(&'static str),
+///     Logout(&'static str),
+/// }
+///
+/// let online = Arc::new(Mutex::new(Vec::new()));
+/// let mut bus = EventBus::new();
+/// let sink = online.clone();
+/// bus.subscribe(move |event: &Event| match event {
+///     Event::Login(name) => sink.lock().unwrap().push(*name),
+///     Event::Logout(name) => sink.lock().unwrap().retain(|n| n != name),
+/// });
+///
+/// bus.publish(&Event::Login("ada"));
+/// bus.publish(&Event::Login("bob"));
+/// bus.publish(&Event::Logout("ada"));
+/// assert_eq!(*online.lock().unwrap(), vec!["bob"]);
+/// ```
+pub struct EventBus<E> {
+    observable: Observable<E>,
+}
+
+impl<E> EventBus<E> {
+    pub fn new() -> EventBus<E> {
+        EventBus { observable: Observable::builder().auto_prune_threshold(0.5).build() }
+    }
+    /// Calls `handler` with every published event until the returned [`Subscription`] is
+    /// passed to [`unsubscribe`](Self::unsubscribe).
+    pub fn subscribe<F>(&mut self, handler: F) -> Subscription
+    where
+        F: FnMut(&E) + Send + 'static,
+    {
+        self.observable.register_fn(handler)
+    }
+    /// Subscribes an observer the bus does not own; it is dropped from the bus once
+    /// nothing else keeps it alive.
+    pub fn subscribe_observer(&mut self, observer: Weak<Mutex<dyn Observer<E>>>) -> Subscription {
+        self.observable.register(observer)
+    }
+    /// Removes a subscription, returning whether it was still registered.
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        self.observable.unregister(subscription)
+    }
+    /// Delivers `event` to every subscriber, returning how many were notified.
+    pub fn publish(&self, event: &E) -> usize {
+        self.observable.send_to_all(event)
+    }
+    pub fn observable(&self) -> &Observable<E> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<E> {
+        &mut self.observable
+    }
+}
+
+impl<E> Default for EventBus<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::bus::*;
+    use crate::test_util::{received, recorder};
+    use std::sync::Arc;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Event {
+        Started(u32),
+        Stopped,
+    }
+
+    #[test]
+    fn test_event_bus() {
+        let mut bus = EventBus::<Event>::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let handler = bus.subscribe(move |event| sink.lock().unwrap().push(event.clone()));
+        let (ob, weak) = recorder();
+        bus.subscribe_observer(weak);
+
+        assert_eq!(bus.publish(&Event::Started(1)), 2);
+        assert_eq!(bus.publish(&Event::Stopped), 2);
+        assert_eq!(*seen.lock().unwrap(), vec![Event::Started(1), Event::Stopped]);
+        assert_eq!(received(&ob), vec![Event::Started(1), Event::Stopped]);
+
+        assert!(bus.unsubscribe(handler));
+        drop(ob);
+        assert_eq!(bus.publish(&Event::Stopped), 0);
+        assert_eq!(seen.lock().unwrap().len(), 2);
+
+        // the dead observer is pruned once it makes up more than half the entries
+        bus.subscribe(|_| {});
+        assert_eq!(bus.observable().len(), 1);
+    }
+}
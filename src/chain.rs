@@ -0,0 +1,153 @@
+use std::{
+    any::Any,
+    collections::HashSet,
+    mem,
+    sync::{Arc, Mutex, Weak},
+};
+
+use crate::{Observable, Observer, RegisterError, Subscription, DISPATCHING};
+
+// Sends everything it hears on to `target`. `address` is where the target observable
+// lives inside its mutex, taken once at `chain_to`, so a cycle can be spotted without
+// locking the mutex again.
+struct Forwarder<T: ?Sized> {
+    target: Weak<Mutex<Observable<T>>>,
+    address: usize,
+}
+
+impl<T: ?Sized + 'static> Observer<T> for Forwarder<T> {
+    fn notify(&mut self, event: &T) {
+        // `chain_to` refuses cycles, but the target already dispatching on this thread
+        // would still mean one, with its mutex likely held further up the stack
+        if DISPATCHING.with_borrow(|dispatching| dispatching.contains(&self.address)) {
+            return;
+        }
+        if let Some(target) = self.target.upgrade() {
+            if let Ok(target) = target.lock() {
+                target.send_to_all(event);
+            }
+        }
+    }
+    // how `forwards_to` tells forwarders from other subscribers
+    fn as_any(&self) -> Option<&dyn Any> {
+        Some(self)
+    }
+}
+
+// Whether following forwarders from `observable` leads to the observable at `address`.
+// `seen` holds the addresses already walked, which are not walked again.
+fn forwards_to<T: ?Sized + 'static>(observable: &Observable<T>, address: usize, seen: &mut HashSet<usize>) -> bool {
+    observable.upgrade_all().iter().any(|o| {
+        let link = o.lock().ok().and_then(|o| {
+            let forwarder = o.as_any()?.downcast_ref::<Forwarder<T>>()?;
+            Some((forwarder.target.clone(), forwarder.address))
+        });
+        let Some((target, next)) = link else {
+            return false;
+        };
+        if next == address {
+            return true;
+        }
+        seen.insert(next) && target.upgrade().is_some_and(|target| target.lock().is_ok_and(|t| forwards_to(&t, address, seen)))
+    })
+}
+
+impl<T: ?Sized + 'static> Observable<T> {
+    /// Forwards every event sent to all subscribers of this observable to `other`'s
+    /// subscribers, until `other` is dropped or the returned [`Subscription`] is
+    /// unregistered. The forwarder is a subscriber like any other, counted among those
+    /// notified. Returns `Ok(None)`, chaining nothing, if `other` is already gone.
+    ///
+    /// Fails with [`RegisterError::Cycle`] when the chain would lead back here: when
+    /// `other` is this observable itself, or already forwards to it, directly or through
+    /// further chains. Fails with [`RegisterError::Closed`] once this observable has been
+    /// shut down.
+    ///
+    /// Finding out locks `other` and every observable chained from it in turn, as well as
+    /// their subscribers, so none of them may be held by the caller, this observable's own
+    /// mutex aside. This observable is recognised by its address, so it must live where
+    /// chains reach it: inside the mutex its [`Weak`]s point to.
+    pub fn chain_to(&mut self, other: Weak<Mutex<Observable<T>>>) -> Result<Option<Subscription>, RegisterError> {
+        if self.is_closed() {
+            return Err(RegisterError::Closed);
+        }
+        let this = self as *const Observable<T> as usize;
+        let Some(target) = other.upgrade() else {
+            return Ok(None);
+        };
+        // a link to itself is told by address alone: the caller likely holds the mutex
+        let inside = Arc::as_ptr(&target) as usize;
+        if (inside..inside + mem::size_of::<Mutex<Observable<T>>>()).contains(&this) {
+            return Err(RegisterError::Cycle);
+        }
+        let Ok(target) = target.lock() else {
+            return Ok(None);
+        };
+        let address = &*target as *const Observable<T> as usize;
+        if forwards_to(&target, this, &mut HashSet::from([address])) {
+            return Err(RegisterError::Cycle);
+        }
+        drop(target);
+        Ok(Some(self.register_owned(Arc::new(Mutex::new(Forwarder { target: other, address })))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::chain::*;
+    use crate::test_util::{received, recorder};
+
+    #[test]
+    fn test_chain_to() {
+        let a = Arc::new(Mutex::new(Observable::<u32>::new()));
+        let b = Arc::new(Mutex::new(Observable::<u32>::new()));
+        let (ob_a, weak_a) = recorder();
+        let (ob_b, weak_b) = recorder();
+        a.lock().unwrap().register(weak_a);
+        b.lock().unwrap().register(weak_b);
+
+        let link = a.lock().unwrap().chain_to(Arc::downgrade(&b)).unwrap().unwrap();
+        assert_eq!(a.lock().unwrap().send_to_all(&1), 2);
+        assert_eq!(received(&ob_b), vec![1]);
+
+        // closing the cycle is refused at the second link
+        assert_eq!(b.lock().unwrap().chain_to(Arc::downgrade(&a)).err(), Some(RegisterError::Cycle));
+        a.lock().unwrap().send_to_all(&2);
+        b.lock().unwrap().send_to_all(&3);
+        assert_eq!(received(&ob_a), vec![1, 2]);
+        assert_eq!(received(&ob_b), vec![1, 2, 3]);
+
+        assert!(a.lock().unwrap().unregister(link));
+        a.lock().unwrap().send_to_all(&4);
+        assert_eq!(received(&ob_b), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_chain_to_rejects_cycles() {
+        let [a, b, c] = [(); 3].map(|_| Arc::new(Mutex::new(Observable::<u32>::new())));
+        let (ob_c, weak_c) = recorder();
+        c.lock().unwrap().register(weak_c);
+
+        // a line is fine, closing it into a loop is not
+        assert!(a.lock().unwrap().chain_to(Arc::downgrade(&b)).unwrap().is_some());
+        assert!(b.lock().unwrap().chain_to(Arc::downgrade(&c)).unwrap().is_some());
+        assert_eq!(c.lock().unwrap().chain_to(Arc::downgrade(&a)).err(), Some(RegisterError::Cycle));
+        assert_eq!(a.lock().unwrap().send_to_all(&1), 1);
+        assert_eq!(received(&ob_c), vec![1]);
+
+        // a second path to the same observable is no cycle
+        assert!(a.lock().unwrap().chain_to(Arc::downgrade(&c)).unwrap().is_some());
+
+        let gone = Arc::downgrade(&Arc::new(Mutex::new(Observable::<u32>::new())));
+        assert!(a.lock().unwrap().chain_to(gone).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_chain_to_self() {
+        let a = Arc::new(Mutex::new(Observable::<u32>::new()));
+        let mut locked = a.lock().unwrap();
+        assert_eq!(locked.chain_to(Arc::downgrade(&a)).err(), Some(RegisterError::Cycle));
+        assert!(locked.is_empty());
+    }
+}
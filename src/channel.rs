@@ -0,0 +1,108 @@
+use std::sync::{mpsc::{self, Receiver, Sender}, Arc, Mutex};
+
+use crate::{NotifyAction, Observable, Observer, Options, Subscriber};
+
+// Forwards clones of every event into a channel and unsubscribes itself on the first
+// send that finds the receiving end gone.
+struct ChannelObserver<T> {
+    sender: Sender<T>,
+}
+
+impl<T: Clone + Send> Observer<T> for ChannelObserver<T> {
+    fn notify(&mut self, event: &T) {
+        let _ = self.sender.send(event.clone());
+    }
+    fn notify_action(&mut self, event: &T) -> NotifyAction {
+        match self.sender.send(event.clone()) {
+            Ok(()) => NotifyAction::Keep,
+            Err(_) => NotifyAction::Unsubscribe,
+        }
+    }
+}
+
+/// Forwards a clone of every event into the channel, so a sender can be registered as
+/// it is and the events consumed from its [`Receiver`].
+///
+/// Each event is cloned because the observer only borrows it. Once the receiver is
+/// gone, events are dropped silently and the sender stays registered; use
+/// [`Observable::subscribe_channel`] for a channel that unsubscribes itself instead.
+impl<T: Clone + Send> Observer<T> for Sender<T> {
+    fn notify(&mut self, event: &T) {
+        let _ = self.send(event.clone());
+    }
+}
+
+// `Clone` keeps this `Sized`: the channel carries owned copies of the events
+impl<T: Clone + Send + 'static> Observable<T> {
+    /// Returns a receiver that gets a clone of every message sent from now on.
+    ///
+    /// The forwarding observer is owned by the observable. Dropping the receiver does not
+    /// detach it by itself: the first send after that still counts the observer as
+    /// notified, but fails to reach the channel and unsubscribes it; the entry is then
+    /// removed by [`prune`](Self::prune).
+    pub fn subscribe_channel(&mut self) -> Receiver<T> {
+        self.subscribe_to_channel(false)
+    }
+    /// Returns a receiver that gets a clone of the next message only; the forwarding
+    /// observer is retired after that single delivery.
+    pub fn subscribe_oneshot(&mut self) -> Receiver<T> {
+        self.subscribe_to_channel(true)
+    }
+    fn subscribe_to_channel(&mut self, once: bool) -> Receiver<T> {
+        let (sender, receiver) = mpsc::channel();
+        let observer = Arc::new(Mutex::new(ChannelObserver { sender }));
+        self.insert(Subscriber::Owned(observer), Options { once, ..Options::default() });
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::channel::*;
+    use std::sync::mpsc::TryRecvError;
+
+    #[test]
+    fn test_subscribe_channel() {
+        let mut observable = Observable::<usize>::new();
+        let all = observable.subscribe_channel();
+        let first = observable.subscribe_oneshot();
+
+        assert_eq!(observable.send_to_all(&1), 2);
+        assert_eq!(observable.send_to_all(&2), 1);
+
+        assert_eq!(all.recv(), Ok(1));
+        assert_eq!(all.recv(), Ok(2));
+        assert_eq!(first.recv(), Ok(1));
+        assert_eq!(first.try_recv(), Err(TryRecvError::Empty));
+
+        assert_eq!(observable.prune(), 1);
+        assert_eq!(first.try_recv(), Err(TryRecvError::Disconnected));
+    }
+
+    #[test]
+    fn test_sender_observer() {
+        let mut observable = Observable::<usize>::new();
+        let (sender, receiver) = mpsc::channel();
+        let sender = Arc::new(Mutex::new(sender));
+        observable.register_concrete(&sender);
+
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(receiver.try_recv(), Ok(1));
+
+        drop(receiver);
+        assert_eq!(observable.send_to_all(&2), 1);
+        assert_eq!(observable.live_count(), 1);
+    }
+
+    #[test]
+    fn test_subscribe_channel_receiver_dropped() {
+        let mut observable = Observable::<usize>::new();
+        drop(observable.subscribe_channel());
+
+        assert_eq!(observable.live_count(), 1);
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(observable.live_count(), 0);
+        assert_eq!(observable.prune(), 1);
+    }
+}
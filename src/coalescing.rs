@@ -0,0 +1,86 @@
+use std::sync::{Mutex, PoisonError, Weak};
+
+use crate::{Observable, Observer, Subscription};
+
+/// An [`Observable`] that queues messages and delivers them on [`flush`](Self::flush),
+/// collapsing runs of equal messages into one.
+///
+/// Only consecutive duplicates are coalesced: queueing `a, a, b, a` delivers `a, b, a`,
+/// so the relative order of distinct messages is kept.
+pub struct CoalescingObservable<T> {
+    observable: Observable<T>,
+    // behind a lock so that `flush` can drain it through `&self`
+    pending: Mutex<Vec<T>>,
+}
+
+impl<T: PartialEq> CoalescingObservable<T> {
+    pub fn new() -> CoalescingObservable<T> {
+        CoalescingObservable {
+            observable: Observable::new(),
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+    pub fn register(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.observable.register(observer)
+    }
+    /// Queues `message` for the next flush, unless it equals the last queued message.
+    pub fn queue(&mut self, message: T) {
+        let pending = self.pending.get_mut().unwrap_or_else(PoisonError::into_inner);
+        if pending.last() != Some(&message) {
+            pending.push(message);
+        }
+    }
+    /// Sends every queued message to all subscribers, returning the number of deliveries.
+    pub fn flush(&self) -> usize {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap_or_else(PoisonError::into_inner));
+        pending.iter().map(|message| self.observable.send_to_all(message)).sum()
+    }
+    /// The wrapped observable. Messages sent through it directly are delivered at once,
+    /// ahead of anything still queued.
+    pub fn observable(&self) -> &Observable<T> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<T> {
+        &mut self.observable
+    }
+}
+
+impl<T: PartialEq> Default for CoalescingObservable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::coalescing::*;
+    use crate::test_util::{received, recorder};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Ui {
+        Redraw,
+        Resize(u32),
+    }
+
+    #[test]
+    fn test_coalescing() {
+        let mut observable = CoalescingObservable::<Ui>::new();
+
+        let (ob1, ob1w) = recorder();
+        observable.register(ob1w);
+
+        observable.queue(Ui::Redraw);
+        observable.queue(Ui::Redraw);
+        observable.queue(Ui::Redraw);
+        assert_eq!(observable.flush(), 1);
+        assert_eq!(observable.flush(), 0);
+
+        observable.queue(Ui::Redraw);
+        observable.queue(Ui::Resize(1));
+        observable.queue(Ui::Redraw);
+        assert_eq!(observable.flush(), 3);
+
+        assert_eq!(received(&ob1), vec![Ui::Redraw, Ui::Redraw, Ui::Resize(1), Ui::Redraw]);
+    }
+}
@@ -0,0 +1,93 @@
+use crate::Observer;
+
+/// Treats a list of observers as one, so that they share a single subscription: each
+/// event is forwarded to every child in the order they were added.
+///
+/// The children are locked and delivered to together, as one observer. A child that
+/// panics is not isolated: the panic leaves the later children without the event and
+/// poisons the composite as a whole, which is then handled by the observable's
+/// [`PoisonPolicy`](crate::PoisonPolicy) like any other observer. Send with
+/// [`Observable::send_to_all_isolated`](crate::Observable::send_to_all_isolated) to keep
+/// such a panic from reaching the sender.
+pub struct CompositeObserver<T> {
+    children: Vec<Box<dyn Observer<T>>>,
+}
+
+impl<T> CompositeObserver<T> {
+    pub fn new() -> CompositeObserver<T> {
+        CompositeObserver { children: Vec::new() }
+    }
+    /// Adds `child` after the existing children.
+    pub fn push(&mut self, child: impl Observer<T> + 'static) {
+        self.children.push(Box::new(child));
+    }
+    /// Like [`push`](Self::push), for building a composite in one expression.
+    pub fn with(mut self, child: impl Observer<T> + 'static) -> CompositeObserver<T> {
+        self.push(child);
+        self
+    }
+    pub fn len(&self) -> usize {
+        self.children.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+impl<T> Default for CompositeObserver<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> From<Vec<Box<dyn Observer<T>>>> for CompositeObserver<T> {
+    fn from(children: Vec<Box<dyn Observer<T>>>) -> CompositeObserver<T> {
+        CompositeObserver { children }
+    }
+}
+
+impl<T> Observer<T> for CompositeObserver<T> {
+    fn notify(&mut self, event: &T) {
+        for child in &mut self.children {
+            child.notify(event);
+        }
+    }
+    fn flush(&mut self) {
+        for child in &mut self.children {
+            child.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::composite::*;
+    use crate::Observable;
+    use std::sync::{Arc, Mutex};
+
+    // tags what it sees so that the children can be told apart in one log
+    struct Tagged(&'static str, Arc<Mutex<Vec<(&'static str, u32)>>>);
+
+    impl Observer<u32> for Tagged {
+        fn notify(&mut self, event: &u32) {
+            self.1.lock().unwrap().push((self.0, *event));
+        }
+    }
+
+    #[test]
+    fn test_composite_observer() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let composite = CompositeObserver::new()
+            .with(Tagged("a", log.clone()))
+            .with(Tagged("b", log.clone()));
+        assert_eq!(composite.len(), 2);
+
+        let mut observable = Observable::new();
+        observable.register_owned(Arc::new(Mutex::new(composite)));
+        assert_eq!(observable.len(), 1);
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(observable.send_to_all(&2), 1);
+        assert_eq!(*log.lock().unwrap(), vec![("a", 1), ("b", 1), ("a", 2), ("b", 2)]);
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{DeliveryPosition, GroupId, Observable};
+
+/// The delivery settings of an observable's named subscribers, as exported by
+/// [`Observable::export_config`] and restored by [`Observable::apply_config`].
+///
+/// Observers themselves cannot be saved, so this only records what their registrations
+/// were configured with, keyed by the name given to
+/// [`register_named`](Observable::register_named). It is plain data, to be stored in
+/// whatever format the application uses.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SubscriberConfig {
+    /// One per named subscriber, in delivery order.
+    pub subscribers: Vec<SubscriberSettings>,
+}
+
+/// How one named subscriber was registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberSettings {
+    pub name: String,
+    pub priority: i32,
+    pub position: DeliveryPosition,
+    pub group: GroupId,
+}
+
+impl<T: ?Sized> Observable<T> {
+    /// The settings of every named subscriber, dead or alive. Unnamed subscribers cannot be
+    /// matched up again later and are left out.
+    pub fn export_config(&self) -> SubscriberConfig {
+        let subscribers = self.entries()
+            .filter_map(|(_, s)| {
+                let name = s.options.name.clone()?;
+                Some(SubscriberSettings { name, priority: s.options.priority, position: s.options.position, group: s.options.group })
+            })
+            .collect();
+        SubscriberConfig { subscribers }
+    }
+    /// Gives every subscriber whose name appears in `config` the priority, position and
+    /// group recorded there, and returns how many were updated. When a name appears more
+    /// than once in `config`, the first entry wins.
+    ///
+    /// Delivery order follows the new settings at once; subscribers whose rank ends up the
+    /// same keep their registration order.
+    pub fn apply_config(&mut self, config: &SubscriberConfig) -> usize {
+        let mut updated = 0;
+        for entry in self.slots.iter_mut().flatten() {
+            let settings = config.subscribers.iter().find(|c| entry.options.name.as_ref() == Some(&c.name));
+            if let Some(settings) = settings {
+                entry.options.priority = settings.priority;
+                entry.options.position = settings.position;
+                entry.options.group = settings.group;
+                updated += 1;
+            }
+        }
+        let slots = &self.slots;
+        self.order.sort_by_key(|&i| slots[i].as_ref().map(|s| (s.options.rank(), s.id)));
+        updated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::config::*;
+    use crate::test_util::recorder;
+
+    #[test]
+    fn test_config_round_trip() {
+        let mut observable = Observable::<u8>::new();
+        let (_audit, audit_weak) = recorder();
+        let (_cache, cache_weak) = recorder();
+        observable.register_named("cache", cache_weak.clone());
+        observable.register_named("audit", audit_weak.clone());
+        let (_unnamed, unnamed_weak) = recorder();
+        observable.register_with_priority(unnamed_weak, 3);
+        let config = {
+            let mut config = observable.export_config();
+            config.subscribers[0].priority = 10;
+            config.subscribers[1].position = DeliveryPosition::Last;
+            config.subscribers[1].group = GroupId(2);
+            config
+        };
+
+        // as after a reload: the same names, registered afresh and in another order
+        observable.clear();
+        let audit = observable.register_named("audit", audit_weak);
+        let cache = observable.register_named("cache", cache_weak);
+        observable.register_named("new", recorder::<u8>().1);
+        assert_eq!(observable.apply_config(&config), 2);
+
+        assert_eq!(observable.export_config(), SubscriberConfig {
+            subscribers: vec![
+                SubscriberSettings { name: "cache".into(), priority: 10, position: DeliveryPosition::Normal, group: GroupId::DEFAULT },
+                SubscriberSettings { name: "new".into(), priority: 0, position: DeliveryPosition::Normal, group: GroupId::DEFAULT },
+                SubscriberSettings { name: "audit".into(), priority: 0, position: DeliveryPosition::Last, group: GroupId(2) },
+            ],
+        });
+        let order: Vec<_> = observable.subscriber_names().into_iter().map(|(i, _, _)| i).collect();
+        assert_eq!((order[0], order[2]), (cache.slot, audit.slot));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::Observer;
+
+/// An observer of small `Copy` events that takes them by value.
+pub trait CopyObserver<T: Copy>: Send {
+    fn notify(&mut self, event: T);
+}
+
+/// Bridges a [`CopyObserver`] into an [`Observer`] so it can be registered on a plain
+/// [`Observable`](crate::Observable); each event is copied out of the reference.
+///
+/// A wrapper rather than a blanket impl, which would conflict with every other
+/// `Observer` impl in the crate.
+pub struct ByValue<O>(pub O);
+
+impl<T: Copy, O: CopyObserver<T>> Observer<T> for ByValue<O> {
+    fn notify(&mut self, event: &T) {
+        self.0.notify(*event)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+
+    use crate::copy::*;
+    use crate::Observable;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Copy)]
+    enum Key {
+        Up(u8),
+        Down(u8),
+    }
+
+    #[derive(Default)]
+    struct Pressed {
+        keys: Vec<u8>,
+    }
+
+    impl CopyObserver<Key> for Pressed {
+        fn notify(&mut self, event: Key) {
+            match event {
+                Key::Down(k) => self.keys.push(k),
+                Key::Up(k) => self.keys.retain(|&p| p != k),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct Counter {
+        counter: usize,
+    }
+
+    impl Observer<Key> for Counter {
+        fn notify(&mut self, _event: &Key) {
+            self.counter += 1;
+        }
+    }
+
+    #[test]
+    fn test_by_value() {
+        let mut observable = Observable::<Key>::new();
+
+        let ob1: Arc<Mutex<ByValue<Pressed>>> = Arc::new(Mutex::new(ByValue(Pressed::default())));
+        let ob1d: Arc<Mutex<dyn Observer<Key>>> = ob1.clone();
+        let ob2: Arc<Mutex<Counter>> = Arc::new(Mutex::new(Counter::default()));
+        let ob2d: Arc<Mutex<dyn Observer<Key>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+
+        observable.send_to_all(&Key::Down(1));
+        observable.send_to_all(&Key::Down(2));
+        observable.send_to_all(&Key::Up(1));
+
+        assert_eq!(ob1.lock().ok().map(|v| v.0.keys.clone()), Some(vec![2]));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(3usize));
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::{Mutex, Weak};
+
+use crate::{Observable, Observer, Subscription};
+
+/// A value that can describe how another value of its type differs from it.
+pub trait Diffable {
+    /// What [`diff`](Self::diff) produces, typically a struct of `Option` fields.
+    type Patch;
+    /// The changes that turn `self` into `other`, or `None` if they are equal.
+    fn diff(&self, other: &Self) -> Option<Self::Patch>;
+}
+
+/// Holds the current value of a [`Diffable`] and notifies observers of the changes only:
+/// subscribers implement `Observer<T::Patch>` and receive one patch per update that
+/// changed something.
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use rust_exercise_2_event::{DiffObservable, Diffable, Observer};
+///
+/// #[derive(PartialEq)]
+/// struct Config {
+///     volume: u8,
+///     muted: bool,
+/// }
+///
+/// struct ConfigPatch {
+///     volume: Option<u8>,
+///     muted: Option<bool>,
+/// }
+///
+/// impl Diffable for Config {
+///     type Patch = ConfigPatch;
+///     fn diff(&self, other: &Config) -> Option<ConfigPatch> {
+///         (self != other).then(|| ConfigPatch {
+///             volume: (self.volume != other.volume).then_some(other.volume),
+///             muted: (self.muted != other.muted).then_some(other.muted),
+///         })
+///     }
+/// }
+///
+/// struct Mixer {
+///     volume: u8,
+/// }
+///
+/// impl Observer<ConfigPatch> for Mixer {
+///     fn notify(&mut self, patch: &ConfigPatch) {
+///         if let Some(volume) = patch.volume {
+///             self.volume = volume;
+///         }
+///     }
+/// }
+///
+/// let mut config = DiffObservable::new(Config { volume: 5, muted: false });
+/// let mixer = Arc::new(Mutex::new(Mixer { volume: 5 }));
+/// config.observable_mut().register_concrete(&mixer);
+///
+/// assert_eq!(config.update(Config { volume: 5, muted: false }), 0);
+/// assert_eq!(config.update(Config { volume: 7, muted: false }), 1);
+/// assert_eq!(mixer.lock().unwrap().volume, 7);
+/// ```
+pub struct DiffObservable<T: Diffable> {
+    observable: Observable<T::Patch>,
+    current: T,
+}
+
+impl<T: Diffable> DiffObservable<T> {
+    pub fn new(initial: T) -> DiffObservable<T> {
+        DiffObservable { observable: Observable::new(), current: initial }
+    }
+    pub fn register(&mut self, observer: Weak<Mutex<dyn Observer<T::Patch>>>) -> Subscription {
+        self.observable.register(observer)
+    }
+    /// Replaces the current value with `value` and sends the patch between them to all
+    /// subscribers, returning how many were notified. An update that changes nothing
+    /// sends nothing and returns 0.
+    pub fn update(&mut self, value: T) -> usize {
+        let Some(patch) = self.current.diff(&value) else {
+            return 0;
+        };
+        self.current = value;
+        self.observable.send_to_all(&patch)
+    }
+    pub fn current(&self) -> &T {
+        &self.current
+    }
+    /// The wrapped observable of patches.
+    pub fn observable(&self) -> &Observable<T::Patch> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<T::Patch> {
+        &mut self.observable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::diff::*;
+    use crate::test_util::{received, recorder};
+
+    #[derive(Clone, PartialEq)]
+    struct Window {
+        title: &'static str,
+        width: u32,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum WindowPatch {
+        Title(&'static str),
+        Width(u32),
+        Both(&'static str, u32),
+    }
+
+    impl Diffable for Window {
+        type Patch = WindowPatch;
+        fn diff(&self, other: &Window) -> Option<WindowPatch> {
+            match (self.title != other.title, self.width != other.width) {
+                (false, false) => None,
+                (true, false) => Some(WindowPatch::Title(other.title)),
+                (false, true) => Some(WindowPatch::Width(other.width)),
+                (true, true) => Some(WindowPatch::Both(other.title, other.width)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_diff_observable() {
+        let window = Window { title: "a", width: 100 };
+        let mut observable = DiffObservable::new(window.clone());
+        let (ob1, ob1w) = recorder();
+        observable.register(ob1w);
+
+        assert_eq!(observable.update(window.clone()), 0);
+        assert_eq!(observable.update(Window { width: 200, ..window }), 1);
+        assert_eq!(observable.update(Window { title: "b", width: 200 }), 1);
+        assert_eq!(observable.update(Window { title: "c", width: 50 }), 1);
+        assert_eq!(received(&ob1), vec![WindowPatch::Width(200), WindowPatch::Title("b"), WindowPatch::Both("c", 50)]);
+        assert_eq!(observable.current().width, 50);
+    }
+}
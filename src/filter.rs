@@ -0,0 +1,92 @@
+use crate::{NotifyAction, Observer};
+
+/// Wraps the observer `O`, forwarding it only the events for which `filter` returns
+/// `true`. Registered like any observer, it can wrap a [`MapObserver`] to filter before
+/// converting, or be wrapped by one's downstream observable to filter after.
+///
+/// The observable cannot see inside the wrapper, so statistics and send results count it
+/// as notified for every event it is handed, whether or not it forwards the event. What
+/// reached the inner observer is counted by [`forwarded`](Self::forwarded).
+///
+/// [`MapObserver`]: crate::MapObserver
+pub struct FilterObserver<O, F> {
+    inner: O,
+    filter: F,
+    forwarded: usize,
+}
+
+impl<O, F> FilterObserver<O, F> {
+    pub fn new(inner: O, filter: F) -> FilterObserver<O, F> {
+        FilterObserver { inner, filter, forwarded: 0 }
+    }
+    /// Number of events passed on to the inner observer so far.
+    pub fn forwarded(&self) -> usize {
+        self.forwarded
+    }
+    pub fn inner(&self) -> &O {
+        &self.inner
+    }
+    pub fn inner_mut(&mut self) -> &mut O {
+        &mut self.inner
+    }
+    pub fn into_inner(self) -> O {
+        self.inner
+    }
+}
+
+impl<T, O, F> Observer<T> for FilterObserver<O, F>
+where
+    O: Observer<T>,
+    F: Fn(&T) -> bool + Send,
+{
+    fn notify(&mut self, event: &T) {
+        if (self.filter)(event) {
+            self.forwarded += 1;
+            self.inner.notify(event);
+        }
+    }
+    // forwarded so that the inner observer can still unsubscribe, from a matching event
+    fn notify_action(&mut self, event: &T) -> NotifyAction {
+        if !(self.filter)(event) {
+            return NotifyAction::Keep;
+        }
+        self.forwarded += 1;
+        self.inner.notify_action(event)
+    }
+    fn flush(&mut self) {
+        self.inner.flush()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+
+    use crate::filter::*;
+    use crate::Observable;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Collect(Vec<u32>);
+
+    impl Observer<u32> for Collect {
+        fn notify(&mut self, event: &u32) {
+            self.0.push(*event);
+        }
+    }
+
+    #[test]
+    fn test_filter_observer() {
+        let mut observable = Observable::new();
+        let even = Arc::new(Mutex::new(FilterObserver::new(Collect::default(), |n: &u32| n.is_multiple_of(2))));
+        observable.register_concrete(&even);
+
+        for n in 1..=5 {
+            observable.send_to_all(&n);
+        }
+        let even = even.lock().unwrap();
+        assert_eq!(even.inner().0, vec![2, 4]);
+        assert_eq!(even.forwarded(), 2);
+        // counted as notified for every event, forwarded or not
+        assert_eq!(observable.stats().total_delivered, 5);
+    }
+}
@@ -1,130 +1,5607 @@
-use std::{vec::Vec, sync::{Arc,Weak,Mutex}};
+//! A minimal observer pattern: an [`Observable`] holds weak references to
+//! [`Observer`]s and forwards messages to every one that is still alive.
+//!
+//! Without the default `std` feature the allocation-free [`ArrayObservable`] is the only
+//! observable available.
+#![cfg_attr(feature = "std", doc = r#"
+```
+use std::sync::{Arc, Mutex};
+use rust_exercise_2_event::{Observable, Observer};
 
-pub(crate) struct Observable<T> {
-    pub(crate) subscribers: Vec<Weak<Mutex<dyn Observer<T>>>>,
+#[derive(Default)]
+struct MyObserver {
+    received: Vec<String>,
 }
 
-impl<T> Observable<T> {
-    pub(crate) fn new() -> Observable<T> {
+impl Observer<String> for MyObserver {
+    fn notify(&mut self, event: &String) {
+        self.received.push(event.clone());
+    }
+}
+
+let mut observable = Observable::<String>::new();
+let observer = Arc::new(Mutex::new(MyObserver::default()));
+let dyn_observer: Arc<Mutex<dyn Observer<String>>> = observer.clone();
+observable.register(Arc::downgrade(&dyn_observer));
+
+assert_eq!(observable.send_to_all(&"hello".to_string()), 1);
+assert_eq!(observer.lock().unwrap().received, vec!["hello".to_string()]);
+```
+"#)]
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use core::any::Any;
+#[cfg(feature = "std")]
+use std::{any::TypeId, cell::RefCell, collections::{HashSet, VecDeque}, fmt, error::Error, vec::Vec, ops::RangeBounds, panic::{self, AssertUnwindSafe}, thread::JoinHandle, time::{Duration, Instant}, sync::{Arc,Weak,Mutex,MutexGuard,PoisonError,TryLockError,mpsc::{self,Sender},atomic::{AtomicBool,AtomicU64,AtomicUsize,Ordering}}};
+
+#[cfg(feature = "std")]
+mod any_observable;
+mod array;
+#[cfg(feature = "std")]
+mod backref;
+#[cfg(feature = "std")]
+mod batching;
+#[cfg(feature = "std")]
+mod builder;
+#[cfg(feature = "std")]
+mod bus;
+#[cfg(feature = "std")]
+mod chain;
+#[cfg(feature = "std")]
+mod channel;
+#[cfg(feature = "std")]
+mod coalescing;
+#[cfg(feature = "std")]
+mod composite;
+#[cfg(feature = "std")]
+mod config;
+mod copy;
+#[cfg(feature = "std")]
+mod diff;
+mod filter;
+#[cfg(feature = "std")]
+mod local;
+#[cfg(feature = "std")]
+mod logger;
+mod macros;
+#[cfg(feature = "std")]
+mod map;
+#[cfg(feature = "std")]
+mod metrics;
+#[cfg(feature = "std")]
+mod middleware;
+#[cfg(feature = "std")]
+mod order;
+#[cfg(feature = "std")]
+mod reducer;
+#[cfg(feature = "std")]
+mod replay;
+#[cfg(feature = "std")]
+mod rw_observable;
+#[cfg(feature = "std")]
+mod sampled;
+#[cfg(feature = "std")]
+mod scoped;
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+mod snapshot;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(feature = "std")]
+mod threaded;
+#[cfg(feature = "std")]
+mod throttled;
+#[cfg(feature = "std")]
+mod topic;
+#[cfg(feature = "std")]
+mod trace;
+#[cfg(feature = "std")]
+mod typed;
+#[cfg(feature = "std")]
+mod typed_channel;
+#[cfg(feature = "std")]
+mod writer;
+#[cfg(feature = "async")]
+mod async_observable;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(all(test, feature = "std"))]
+mod test_util;
+
+#[cfg(feature = "std")]
+pub use any_observable::AnyObservable;
+pub use array::ArrayObservable;
+#[cfg(feature = "std")]
+pub use backref::SourceAware;
+#[cfg(feature = "std")]
+pub use batching::{BatchObserver, BatchingObservable};
+#[cfg(feature = "std")]
+pub use builder::{NoReplay, ObservableBuilder, Replay};
+#[cfg(feature = "std")]
+pub use bus::EventBus;
+#[cfg(feature = "std")]
+pub use coalescing::CoalescingObservable;
+#[cfg(feature = "std")]
+pub use composite::CompositeObserver;
+#[cfg(feature = "std")]
+pub use config::{SubscriberConfig, SubscriberSettings};
+pub use copy::{ByValue, CopyObserver};
+#[cfg(feature = "std")]
+pub use diff::{DiffObservable, Diffable};
+pub use filter::FilterObserver;
+#[cfg(feature = "std")]
+pub use local::LocalObservable;
+#[cfg(feature = "std")]
+pub use map::{ConvertingForwarder, MapObserver};
+#[cfg(feature = "metrics")]
+pub use metrics::LatencyReport;
+#[cfg(feature = "std")]
+pub use middleware::Middleware;
+#[cfg(feature = "std")]
+pub use order::{DeliveryOrder, Random, RoundRobin, Reverse, Sequential};
+#[cfg(feature = "std")]
+pub use reducer::ReducingObservable;
+#[cfg(feature = "std")]
+pub use replay::{ReplayObservable, SequenceGap};
+#[cfg(feature = "std")]
+pub use rw_observable::RwObservable;
+#[cfg(feature = "std")]
+pub use sampled::SampledObservable;
+#[cfg(feature = "std")]
+pub use scoped::{BorrowObserver, ScopedObservable};
+#[cfg(feature = "std")]
+pub use shared::SharedObservable;
+#[cfg(feature = "std")]
+pub use snapshot::{ObservableSnapshot, SubscriberSnapshot};
+#[cfg(feature = "testing")]
+pub use testing::TestObserver;
+#[cfg(feature = "std")]
+pub use threaded::ThreadedObserver;
+#[cfg(feature = "std")]
+pub use throttled::ThrottledObservable;
+#[cfg(feature = "std")]
+pub use topic::TopicObservable;
+#[cfg(feature = "tracing")]
+pub use trace::Tracer;
+#[cfg(feature = "std")]
+pub use typed::TypedObservable;
+#[cfg(feature = "std")]
+pub use typed_channel::Channel;
+#[cfg(feature = "std")]
+pub use writer::{WriteErrorPolicy, WriterObserver};
+#[cfg(feature = "async")]
+pub use async_observable::{AsyncMutex, AsyncMutexGuard, AsyncObservable, AsyncObserver, BoxFuture, Lock};
+#[cfg(feature = "stream")]
+pub use stream::{BufferPolicy, EventStream, Recv};
+
+#[cfg(feature = "std")]
+/// Forwards messages to registered [`Observer`]s.
+///
+/// Subscribers are notified in descending priority order (see
+/// [`register_with_priority`](Self::register_with_priority)), ties in registration order.
+///
+/// Each registration occupies a slot, and indices such as those taken by
+/// [`send_to`](Self::send_to) name slots, not positions in delivery order: an index stays
+/// with its subscriber until [`unregister`](Self::unregister), [`prune`](Self::prune) or
+/// friends remove it. The vacated slot is then handed to the next registration, so heavy
+/// subscription churn does not grow the storage. An index held across a removal may
+/// therefore name a newer subscriber; a [`Subscription`] also remembers the never-reused
+/// id of its registration and stops matching once its slot has been reused.
+///
+/// # Unsized events
+///
+/// Sends only lend the message, so `T` may be unsized, `str` or `[u8]` say, and no owned
+/// container is needed to send it. The few methods that take or keep messages by value,
+/// such as [`send_batch`](Self::send_batch), [`enqueue`](Self::enqueue) or
+/// [`pause`](Self::pause), require `T: Sized`:
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use rust_exercise_2_event::{Observable, Observer};
+///
+/// struct Words(usize);
+///
+/// impl Observer<str> for Words {
+///     fn notify(&mut self, line: &str) {
+///         self.0 += line.split_whitespace().count();
+///     }
+/// }
+///
+/// let mut observable = Observable::<str>::new();
+/// let words = Arc::new(Mutex::new(Words(0)));
+/// observable.register_concrete(&words);
+/// observable.send_to_all("the quick brown fox");
+/// assert_eq!(words.lock().unwrap().0, 4);
+/// ```
+///
+/// # Thread safety
+///
+/// Subscribers are stored as `Arc<Mutex<dyn Observer<T>>>` and [`Observer`] requires
+/// `Send`, so an `Observable<T>` is `Send + Sync` for every `T`: messages are only ever
+/// borrowed for the duration of a call, on the calling thread. The one method that
+/// hands `&T` to other threads, [`send_to_all_parallel`](Self::send_to_all_parallel),
+/// requires `T: Sync`:
+///
+/// ```compile_fail
+/// use std::cell::Cell;
+/// use rust_exercise_2_event::Observable;
+///
+/// let observable = Observable::<Cell<u8>>::new();
+/// observable.send_to_all_parallel(&Cell::new(1));
+/// ```
+pub struct Observable<T: ?Sized> {
+    // indexed by slot; `None` marks a slot vacated by a removal
+    slots: Vec<Option<Entry<T>>>,
+    // vacated slots, reused most recent first
+    free: Vec<usize>,
+    // generation of each slot ever used, bumped whenever the slot is handed out again;
+    // outlives `clear` so that handles from before it stay stale
+    epochs: Vec<u32>,
+    // occupied slots in delivery order: by position, then descending priority, ties in
+    // registration order
+    order: Vec<usize>,
+    next_id: u64,
+    capacity: Option<usize>,
+    auto_prune: Option<f64>,
+    poison_policy: PoisonPolicy,
+    logger: Option<logger::Logger<T>>,
+    #[cfg(feature = "tracing")]
+    tracer: Option<Box<dyn Tracer>>,
+    #[cfg(feature = "metrics")]
+    latency: metrics::Latency,
+    // locked by `send_to_all_owned`, which only borrows the observable
+    middleware: Mutex<Vec<Box<dyn Middleware<T>>>>,
+    // messages waiting for `enqueue` to deliver them; type-erased like `Entry::last`
+    // so that the observable stays `Sync` for every `T`
+    queue: Mutex<VecDeque<Box<dyn Any + Send>>>,
+    stats: Arc<Stats>,
+    sequence: AtomicU64,
+    cursor: usize,
+    // the most recent ids seen by `send_dedup`, oldest first, at most `dedup_window`
+    recent_ids: VecDeque<EventId>,
+    dedup_window: usize,
+    // groups switched off with `set_group_enabled`; every other group is enabled
+    disabled_groups: HashSet<GroupId>,
+    // set by `pause` to how a send copies its message into `paused`; the bound `T: Clone`
+    // lives on `pause`, so that `send_to_all` does not need it
+    pause: Option<CopyFn<T>>,
+    paused: Mutex<Vec<Box<dyn Any + Send>>>,
+    // set by `shutdown`, after which registrations are dropped
+    closed: bool,
+    // `send_to_all` calls after which the next `&mut self` call prunes; 0 for never
+    prune_every: usize,
+    sends_since_prune: AtomicUsize,
+    last_prune: PruneStats,
+    // set by `set_order`; `None` keeps `order` as it is without collecting the recipients
+    delivery_order: Option<Box<dyn DeliveryOrder>>,
+    // set by `set_dead_letter`; outside `slots`, so that no send or count sees it
+    dead_letter: Option<Weak<Mutex<dyn Observer<T>>>>,
+    // draws for `send_to_weighted_random`
+    rng: order::Random,
+}
+
+#[cfg(feature = "std")]
+type CopyFn<T> = fn(&T) -> Box<dyn Any + Send>;
+
+// Atomics rather than `Cell`s: sends take `&self` and the observable must stay `Sync`.
+#[cfg(feature = "std")]
+#[derive(Default)]
+struct Stats {
+    sent: AtomicUsize,
+    delivered: AtomicUsize,
+    dropped: AtomicUsize,
+}
+
+#[cfg(feature = "std")]
+impl Stats {
+    // Returns how many of `results` were delivered.
+    fn record(&self, sent: usize, results: impl IntoIterator<Item = DeliveryResult>) -> usize {
+        let (delivered, dropped) = results.into_iter().fold((0, 0), |(delivered, dropped), r| match r {
+            DeliveryResult::Delivered => (delivered + 1, dropped),
+            _ => (delivered, dropped + 1),
+        });
+        self.sent.fetch_add(sent, Ordering::Relaxed);
+        self.delivered.fetch_add(delivered, Ordering::Relaxed);
+        self.dropped.fetch_add(dropped, Ordering::Relaxed);
+        delivered
+    }
+}
+
+#[cfg(feature = "std")]
+/// Counters returned by [`Observable::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ObservableStats {
+    /// Messages dispatched, counting each message of a batch.
+    pub total_sent: usize,
+    /// Successful (observer, message) deliveries.
+    pub total_delivered: usize,
+    /// Deliveries that failed because the subscriber was dead or poisoned.
+    pub total_dropped: usize,
+}
+
+#[cfg(feature = "std")]
+/// What the latest [`Observable::prune`] removed, returned by
+/// [`Observable::last_prune_stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PruneStats {
+    /// Entries removed.
+    pub pruned: usize,
+    /// The most sends any removed entry was tried by after its observer was gone: how
+    /// long the longest-lingering dead entry cost the sends before being cleaned up.
+    pub max_stale_sends: usize,
+}
+
+#[cfg(feature = "std")]
+struct Entry<T: ?Sized> {
+    id: u64,
+    observer: Subscriber<T>,
+    cancelled: Arc<AtomicBool>,
+    options: Options,
+    // last message `send_if_changed` delivered here; type-erased so that storing it does
+    // not tie the observable's `Send`/`Sync` to `T`
+    last: Option<Arc<dyn Any + Send + Sync>>,
+    // messages handed to the observer, whichever send delivered them
+    delivered: AtomicU64,
+    // sends that found the observer gone, for `last_prune_stats`
+    stale_sends: AtomicUsize,
+    // the observable's statistics, whose send count numbers the send in progress
+    stats: Arc<Stats>,
+    // number of the last send that delivered here, 0 before the first
+    last_sequence: AtomicU64,
+}
+
+// Per-registration settings chosen by the different `register_*` methods.
+#[cfg(feature = "std")]
+#[derive(Clone, Default)]
+struct Options {
+    once: bool,
+    priority: i32,
+    position: DeliveryPosition,
+    // a `String` for `register_on`, a key of the `TopicObservable`'s type for that
+    topic: Option<Arc<dyn Any + Send + Sync>>,
+    name: Option<String>,
+    // concrete observer type, known when registered through `register_concrete`
+    type_id: Option<TypeId>,
+    group: GroupId,
+    // chance of being picked by `send_to_weighted_random`, relative to the others; `None`
+    // for the weight 1 of every other registration
+    weight: Option<f64>,
+}
+
+#[cfg(feature = "std")]
+impl Options {
+    // What delivery order sorts by, smallest first.
+    fn rank(&self) -> (DeliveryPosition, core::cmp::Reverse<i32>) {
+        (self.position, core::cmp::Reverse(self.priority))
+    }
+}
+
+#[cfg(feature = "std")]
+enum Subscriber<T: ?Sized> {
+    Weak(Weak<Mutex<dyn Observer<T>>>),
+    Owned(Arc<Mutex<dyn Observer<T>>>),
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> Subscriber<T> {
+    fn upgrade(&self) -> Option<Arc<Mutex<dyn Observer<T>>>> {
+        match self {
+            Subscriber::Weak(w) => w.upgrade(),
+            Subscriber::Owned(a) => Some(a.clone()),
+        }
+    }
+    // Strong and weak counts of the observer's `Arc`, read without upgrading so that the
+    // strong count is not inflated by the reading itself.
+    fn counts(&self) -> (usize, usize) {
+        match self {
+            Subscriber::Weak(w) => (w.strong_count(), w.weak_count()),
+            Subscriber::Owned(a) => (Arc::strong_count(a), Arc::weak_count(a)),
+        }
+    }
+    fn ptr_eq(&self, other: &Weak<Mutex<dyn Observer<T>>>) -> bool {
+        match self {
+            Subscriber::Weak(w) => Weak::ptr_eq(w, other),
+            Subscriber::Owned(a) => std::ptr::addr_eq(Arc::as_ptr(a), other.as_ptr()),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> Clone for Subscriber<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Subscriber::Weak(w) => Subscriber::Weak(w.clone()),
+            Subscriber::Owned(a) => Subscriber::Owned(a.clone()),
+        }
+    }
+}
+
+// A copy of the registration under a flag of its own, so that unsubscribing one copy,
+// through its handle or from `notify`, leaves the other registered.
+#[cfg(feature = "std")]
+impl<T: ?Sized> Clone for Entry<T> {
+    fn clone(&self) -> Self {
+        Entry {
+            id: self.id,
+            observer: self.observer.clone(),
+            cancelled: Arc::new(AtomicBool::new(self.cancelled.load(Ordering::Acquire))),
+            options: self.options.clone(),
+            last: self.last.clone(),
+            delivered: AtomicU64::new(self.delivered.load(Ordering::Relaxed)),
+            stale_sends: AtomicUsize::new(self.stale_sends.load(Ordering::Relaxed)),
+            stats: self.stats.clone(),
+            last_sequence: AtomicU64::new(self.last_sequence.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> Entry<T> {
+    fn upgrade(&self) -> Option<Arc<Mutex<dyn Observer<T>>>> {
+        if self.cancelled.load(Ordering::Acquire) {
+            None
+        } else {
+            self.observer.upgrade()
+        }
+    }
+    // Counts a send that found the observer gone.
+    fn stale(&self) -> DeliveryResult {
+        self.stale_sends.fetch_add(1, Ordering::Relaxed);
+        DeliveryResult::Dropped
+    }
+    fn deliver(&self, message: &T, policy: PoisonPolicy) -> DeliveryResult {
+        match self.deliver_with(policy, |o| o.notify_action(message)) {
+            Ok(action) => {
+                self.apply(action);
+                DeliveryResult::Delivered
+            }
+            Err(r) => r,
+        }
+    }
+    // Upgrades, locks and runs `f`, reporting why it could not when the observer is gone.
+    fn deliver_with<R>(&self, policy: PoisonPolicy, f: impl FnOnce(&mut dyn Observer<T>) -> R) -> Result<R, DeliveryResult> {
+        let Some(observer) = self.upgrade() else {
+            return Err(self.stale());
+        };
+        let mut observer = lock(&observer, policy, &self.cancelled)?;
+        self.call(&mut *observer, f).ok_or(DeliveryResult::Dropped)
+    }
+    fn notify(&self, observer: &mut dyn Observer<T>, message: &T) -> bool {
+        self.call(observer, |o| o.notify_action(message))
+            .map(|action| self.apply(action))
+            .is_some()
+    }
+    // Removal is deferred: the entry is only flagged here and `prune` drops it later.
+    fn apply(&self, action: NotifyAction) {
+        if action == NotifyAction::Unsubscribe {
+            self.cancelled.store(true, Ordering::Release);
+        }
+    }
+    // Every delivery path goes through here with the observer's lock held, so a
+    // one-shot entry is consumed exactly once even under concurrent sends.
+    fn call<R>(&self, observer: &mut dyn Observer<T>, f: impl FnOnce(&mut dyn Observer<T>) -> R) -> Option<R> {
+        if !claim(self.options.once, &self.cancelled) {
+            return None;
+        }
+        // a send adds itself to the count once its deliveries are done
+        self.last_sequence.store(self.stats.sent.load(Ordering::Relaxed) as u64 + 1, Ordering::Relaxed);
+        if self.delivered.fetch_add(1, Ordering::Relaxed) == 0 {
+            Some(f(&mut First(observer)))
+        } else {
+            Some(f(observer))
+        }
+    }
+}
+
+// Stands in for an observer on its first delivery, running `Observer::on_first` ahead of
+// whichever hook the send calls.
+#[cfg(feature = "std")]
+struct First<'a, T: ?Sized>(&'a mut (dyn Observer<T> + 'a));
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> Observer<T> for First<'_, T> {
+    fn notify(&mut self, event: &T) {
+        self.0.on_first(event);
+        self.0.notify(event)
+    }
+    fn notify_action(&mut self, event: &T) -> NotifyAction {
+        self.0.on_first(event);
+        self.0.notify_action(event)
+    }
+    fn notify_stoppable(&mut self, event: &T) -> Propagation {
+        self.0.on_first(event);
+        self.0.notify_stoppable(event)
+    }
+    fn notify_ctx(&mut self, event: &T, ctx: &EventContext) {
+        self.0.on_first(event);
+        self.0.notify_ctx(event, ctx)
+    }
+    fn notify_indexed(&mut self, event: &T, index: usize, total: usize) {
+        self.0.on_first(event);
+        self.0.notify_indexed(event, index, total)
+    }
+    fn notify_ack(&mut self, event: &T) -> bool {
+        self.0.on_first(event);
+        self.0.notify_ack(event)
+    }
+    fn handle(&mut self, event: &T) -> Result<(), String> {
+        self.0.on_first(event);
+        self.0.handle(event)
+    }
+    fn notify_with_reply(&mut self, event: &T, reply: &Reply) {
+        self.0.on_first(event);
+        self.0.notify_with_reply(event, reply)
+    }
+    fn query(&mut self, event: &T) -> Option<Box<dyn Any + Send>> {
+        self.0.on_first(event);
+        self.0.query(event)
+    }
+    fn notify_owned(&mut self, event: T)
+    where
+        T: Sized,
+    {
+        self.0.on_first(&event);
+        self.0.notify_owned(event)
+    }
+    fn wants_owned(&self) -> bool {
+        self.0.wants_owned()
+    }
+    fn flush(&mut self) {
+        self.0.flush()
+    }
+    fn as_any(&self) -> Option<&dyn Any> {
+        self.0.as_any()
+    }
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        self.0.as_any_mut()
+    }
+}
+
+// Whether a delivery may go ahead; a one-shot registration is claimed by the first caller.
+// Checked under the observer's lock, so an entry cancelled by the previous delivery of
+// a batch is not notified again.
+#[cfg(feature = "std")]
+fn claim(once: bool, cancelled: &AtomicBool) -> bool {
+    if once {
+        !cancelled.swap(true, Ordering::AcqRel)
+    } else {
+        !cancelled.load(Ordering::Acquire)
+    }
+}
+
+// Locks `observer`, handling a poisoned mutex as `policy` says. `cancelled` is the
+// entry's flag, raised under `PoisonPolicy::Remove`.
+#[cfg(feature = "std")]
+fn lock<'a, T: ?Sized>(
+    observer: &'a Mutex<dyn Observer<T> + 'static>,
+    policy: PoisonPolicy,
+    cancelled: &AtomicBool,
+) -> Result<MutexGuard<'a, dyn Observer<T> + 'static>, DeliveryResult> {
+    observer.lock().or_else(|poisoned| recover(observer, poisoned, policy, cancelled))
+}
+
+#[cfg(feature = "std")]
+fn recover<'a, T: ?Sized>(
+    observer: &'a Mutex<dyn Observer<T> + 'static>,
+    poisoned: PoisonError<MutexGuard<'a, dyn Observer<T> + 'static>>,
+    policy: PoisonPolicy,
+    cancelled: &AtomicBool,
+) -> Result<MutexGuard<'a, dyn Observer<T> + 'static>, DeliveryResult> {
+    match policy {
+        PoisonPolicy::Skip => Err(DeliveryResult::Poisoned),
+        PoisonPolicy::Recover => {
+            observer.clear_poison();
+            Ok(poisoned.into_inner())
+        }
+        PoisonPolicy::Remove => {
+            cancelled.store(true, Ordering::Release);
+            Err(DeliveryResult::Poisoned)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+/// What a send does with an observer whose mutex was poisoned by a panic, set with
+/// [`Observable::set_poison_policy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoisonPolicy {
+    /// Leave the observer registered but skip it, reporting [`DeliveryResult::Poisoned`].
+    #[default]
+    Skip,
+    /// Clear the poison and notify the observer anyway, trusting it to be in a usable state.
+    Recover,
+    /// Unsubscribe the observer: it is skipped from then on and dropped by the next
+    /// [`prune`](Observable::prune).
+    Remove,
+}
+
+#[cfg(feature = "std")]
+/// Outcome of delivering a message to one subscriber.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryResult {
+    Delivered,
+    /// The observer was dropped or unsubscribed.
+    Dropped,
+    /// The observer's mutex was poisoned by an earlier panic.
+    Poisoned,
+}
+
+#[cfg(feature = "std")]
+impl DeliveryResult {
+    fn notified(notified: bool) -> DeliveryResult {
+        if notified {
+            DeliveryResult::Delivered
+        } else {
+            DeliveryResult::Dropped
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+/// In-flight notifications started by [`Observable::send_to_all_detached`].
+///
+/// Dropping the handle without calling [`wait`](Self::wait) lets the notifications
+/// finish in the background, but their outcome is then missing from
+/// [`Observable::stats`].
+#[must_use = "call `wait` to block until every observer has been notified"]
+pub struct DeliveryJoin {
+    handles: Vec<JoinHandle<DeliveryResult>>,
+    stats: Arc<Stats>,
+}
+
+#[cfg(feature = "std")]
+impl DeliveryJoin {
+    /// Blocks until every spawned notification has finished, returning how many observers
+    /// were notified.
+    pub fn wait(self) -> usize {
+        let results = self.handles.into_iter()
+            // a panicking observer poisons its mutex on the way out
+            .map(|handle| handle.join().unwrap_or(DeliveryResult::Poisoned));
+        self.stats.record(0, results)
+    }
+}
+
+#[cfg(feature = "std")]
+/// Metadata delivered next to a message by [`Observable::send_to_all_with_ctx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventContext {
+    /// Position of the send among those stamped by the same observable, starting at 0.
+    pub sequence: u64,
+    /// When the context was created.
+    pub timestamp: Instant,
+}
+
+#[cfg(feature = "std")]
+/// Identifies a message to [`Observable::send_dedup`], which delivers each id once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EventId(pub u64);
+
+#[cfg(feature = "std")]
+impl From<u64> for EventId {
+    fn from(id: u64) -> EventId {
+        EventId(id)
+    }
+}
+
+#[cfg(feature = "std")]
+/// How hard [`Observable::send_prioritized`] tries to reach an observer that is busy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EventPriority {
+    /// Skipped by an observer whose lock is held elsewhere, to shed load.
+    Low,
+    /// Waits for every observer's lock, as [`Observable::send_to_all`] does.
+    High,
+}
+
+#[cfg(feature = "std")]
+/// Where [`Observable::register_positioned`] places a subscriber in delivery order. It
+/// outranks priority: every `First` subscriber is notified before any `Normal` one, and
+/// every `Last` subscriber after them all, whatever the priorities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum DeliveryPosition {
+    First,
+    #[default]
+    Normal,
+    /// For observers such as audit logs that must see the outcome of everyone else.
+    Last,
+}
+
+#[cfg(feature = "std")]
+/// A category of subscribers that [`Observable::set_group_enabled`] switches on and off
+/// together. Subscribers registered outside [`Observable::register_in_group`] belong to
+/// [`GroupId::DEFAULT`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct GroupId(pub u32);
+
+#[cfg(feature = "std")]
+impl GroupId {
+    pub const DEFAULT: GroupId = GroupId(0);
+}
+
+/// Returned by [`Observer::notify_action`] to keep or end the observer's subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyAction {
+    Keep,
+    /// Stop delivering to this subscription. The entry is skipped from now on and
+    /// removed by the next [`Observable::prune`].
+    Unsubscribe,
+}
+
+/// Returned by [`Observer::notify_stoppable`] to decide whether later subscribers see the event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Propagation {
+    Continue,
+    Stop,
+}
+
+#[cfg(feature = "std")]
+/// Why [`Observable::send_to_checked`] could not deliver a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendError {
+    Dropped,
+    Poisoned,
+    OutOfBounds,
+    /// The handle's slot has been reused by a newer registration.
+    StaleHandle,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for SendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Dropped => write!(f, "observer was dropped"),
+            SendError::Poisoned => write!(f, "observer mutex is poisoned"),
+            SendError::OutOfBounds => write!(f, "no subscriber at that index"),
+            SendError::StaleHandle => write!(f, "subscription handle is stale"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for SendError {}
+
+#[cfg(feature = "std")]
+/// Why [`Observable::replace`] left a subscription untouched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplaceError {
+    /// The registration has been removed and its slot is empty.
+    Removed,
+    /// The handle's slot has been reused by a newer registration.
+    StaleHandle,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for ReplaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplaceError::Removed => write!(f, "subscription was removed"),
+            ReplaceError::StaleHandle => write!(f, "subscription handle is stale"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ReplaceError {}
+
+#[cfg(feature = "std")]
+/// Why [`Observable::try_send_to`] could not deliver a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendToError {
+    /// No subscriber exists at this index, which usually points at a stale index.
+    Index(usize),
+    Dropped,
+    Poisoned,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for SendToError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendToError::Index(i) => write!(f, "no subscriber at index {}", i),
+            SendToError::Dropped => write!(f, "observer was dropped"),
+            SendToError::Poisoned => write!(f, "observer mutex is poisoned"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for SendToError {}
+
+#[cfg(feature = "std")]
+impl From<SendToError> for SendError {
+    fn from(e: SendToError) -> SendError {
+        match e {
+            SendToError::Index(_) => SendError::OutOfBounds,
+            SendToError::Dropped => SendError::Dropped,
+            SendToError::Poisoned => SendError::Poisoned,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+/// Returned by [`Observable::try_send_to_all`] for a send issued from inside one of
+/// that observable's own `notify` calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReentrantSend;
+
+#[cfg(feature = "std")]
+impl fmt::Display for ReentrantSend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "send_to_all called while the same observable is dispatching")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ReentrantSend {}
+
+#[cfg(feature = "std")]
+/// Where an observer answers a message sent with [`Observable::send_collect_replies`].
+///
+/// It can be cloned and moved to another thread to answer later, until the sender stops
+/// waiting. The sender only counts one reply per observer it notified.
+#[derive(Debug, Clone)]
+pub struct Reply {
+    sender: Sender<Box<dyn Any + Send>>,
+}
+
+#[cfg(feature = "std")]
+impl Reply {
+    /// Sends `value` back, returning `false` if the sender has stopped waiting. A value of
+    /// another type than the one the sender collects still counts as the observer's reply,
+    /// but is dropped.
+    pub fn send<R: Send + 'static>(&self, value: R) -> bool {
+        self.sender.send(Box::new(value)).is_ok()
+    }
+}
+
+#[cfg(feature = "std")]
+/// Who processed a message sent with [`Observable::send_to_all_tracked`], by subscriber
+/// index in delivery order.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct AckReport {
+    /// Notified, and acknowledged processing.
+    pub acked: Vec<usize>,
+    /// Notified, but reported that processing failed; candidates for a retry with
+    /// [`Observable::send_to`].
+    pub nacked: Vec<usize>,
+    /// Could not be notified because they were dead or poisoned.
+    pub failed: Vec<usize>,
+}
+
+#[cfg(feature = "std")]
+/// Returned by [`Observable::send_or_err`] when a message reached no subscriber.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoSubscribers;
+
+#[cfg(feature = "std")]
+impl fmt::Display for NoSubscribers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no subscriber received the message")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for NoSubscribers {}
+
+#[cfg(feature = "std")]
+thread_local! {
+    // addresses of the observables currently dispatching on this thread
+    static DISPATCHING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+// Tracked per thread rather than with a flag on the observable, so that sends from
+// other threads are not mistaken for re-entrant ones.
+#[cfg(feature = "std")]
+struct DispatchGuard(usize);
+
+#[cfg(feature = "std")]
+impl DispatchGuard {
+    fn enter<T: ?Sized>(observable: &Observable<T>) -> Result<DispatchGuard, ReentrantSend> {
+        let address = observable as *const Observable<T> as usize;
+        DISPATCHING.with_borrow_mut(|dispatching| {
+            if dispatching.contains(&address) {
+                Err(ReentrantSend)
+            } else {
+                dispatching.push(address);
+                Ok(DispatchGuard(address))
+            }
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for DispatchGuard {
+    fn drop(&mut self) {
+        DISPATCHING.with_borrow_mut(|dispatching| dispatching.retain(|&a| a != self.0));
+    }
+}
+
+#[cfg(feature = "std")]
+/// Why [`Observable::try_register`], [`Observable::register_unique_type`] or
+/// [`Observable::chain_to`] refused a subscriber.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegisterError {
+    /// The observable already has as many live subscribers as its capacity allows: `live`,
+    /// counted after pruning dead entries, is at least `capacity`. A capacity of zero
+    /// refuses every subscriber.
+    Full { capacity: usize, live: usize },
+    /// An observer of the same concrete type is already registered.
+    DuplicateType,
+    /// The observable has been [shut down](Observable::shutdown).
+    Closed,
+    /// The chain would forward events back round to the observable itself.
+    Cycle,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Display for RegisterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegisterError::Full { capacity, live } => {
+                write!(f, "observable is at capacity: {} live subscribers, capacity {}", live, capacity)
+            }
+            RegisterError::DuplicateType => write!(f, "an observer of this type is already registered"),
+            RegisterError::Closed => write!(f, "observable has been shut down"),
+            RegisterError::Cycle => write!(f, "chaining would forward events back to this observable"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for RegisterError {}
+
+#[cfg(feature = "std")]
+/// Handle to a single registration, returned by [`Observable::register`].
+///
+/// A handle records the slot of its registration together with the slot's epoch, which
+/// goes up each time the slot is reused. Slots are recycled once emptied, so the epoch is
+/// what tells a stale handle apart from the newer registration now in its slot: such a
+/// handle matches nothing, while handles of other subscribers stay valid whatever is
+/// removed around them.
+#[derive(Debug)]
+pub struct Subscription {
+    epoch: u32,
+    slot: usize,
+    cancelled: Arc<AtomicBool>,
+    drop_unsubscribes: bool,
+}
+
+#[cfg(feature = "std")]
+impl Subscription {
+    /// When enabled, dropping this handle unsubscribes the observer.
+    pub fn set_drop_unsubscribes(&mut self, enabled: bool) {
+        self.drop_unsubscribes = enabled;
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if self.drop_unsubscribes {
+            self.cancelled.store(true, Ordering::Release);
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+/// One observer's registrations on several observables, returned by [`Observable::merge`].
+///
+/// Dropping the handle unsubscribes the observer from every source.
+#[derive(Debug)]
+pub struct MergedSubscription {
+    subscriptions: Vec<Subscription>,
+}
+
+#[cfg(feature = "std")]
+impl MergedSubscription {
+    /// The per-source handles, in the order the sources were given.
+    pub fn subscriptions(&self) -> &[Subscription] {
+        &self.subscriptions
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> Observable<T> {
+    pub fn new() -> Observable<T> {
         Observable {
-            subscribers: Vec::new(),
+            slots: Vec::new(),
+            free: Vec::new(),
+            epochs: Vec::new(),
+            order: Vec::new(),
+            next_id: 0,
+            capacity: None,
+            auto_prune: None,
+            poison_policy: PoisonPolicy::Skip,
+            logger: None,
+            #[cfg(feature = "tracing")]
+            tracer: None,
+            #[cfg(feature = "metrics")]
+            latency: metrics::Latency::new(),
+            middleware: Mutex::new(Vec::new()),
+            queue: Mutex::new(VecDeque::new()),
+            stats: Arc::default(),
+            sequence: AtomicU64::new(0),
+            cursor: 0,
+            recent_ids: VecDeque::new(),
+            dedup_window: 64,
+            disabled_groups: HashSet::new(),
+            pause: None,
+            paused: Mutex::new(Vec::new()),
+            closed: false,
+            prune_every: 0,
+            sends_since_prune: AtomicUsize::new(0),
+            last_prune: PruneStats::default(),
+            delivery_order: None,
+            dead_letter: None,
+            rng: order::Random::new(),
+        }
+    }
+    /// Creates an observable that accepts at most `max` live subscribers through
+    /// [`try_register`](Self::try_register). `register` and its variants do not check the cap.
+    /// With `max` zero, `try_register` refuses every subscriber.
+    pub fn with_capacity(max: usize) -> Observable<T> {
+        Observable {
+            capacity: Some(max),
+            ..Observable::new()
+        }
+    }
+    /// Chooses how sends treat an observer whose mutex was poisoned by a panic; the default
+    /// is [`PoisonPolicy::Skip`]. [`retain`](Self::retain) always removes such observers.
+    pub fn set_poison_policy(&mut self, policy: PoisonPolicy) {
+        self.poison_policy = policy;
+    }
+    pub fn register(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.insert(Subscriber::Weak(observer), Options::default())
+    }
+    /// Like [`register`](Self::register), but takes the observer under its concrete type and
+    /// does the `dyn Observer<T>` coercion itself. Only a weak reference is kept.
+    pub fn register_concrete<O: Observer<T> + 'static>(&mut self, observer: &Arc<Mutex<O>>) -> Subscription {
+        let observer: Weak<Mutex<O>> = Arc::downgrade(observer);
+        self.insert(Subscriber::Weak(observer), Options { type_id: Some(TypeId::of::<O>()), ..Options::default() })
+    }
+    /// Like [`register_concrete`](Self::register_concrete), but fails while a live observer
+    /// of type `O` is registered through either method.
+    ///
+    /// Dead subscribers are pruned first, so a dropped observer frees its type up again.
+    pub fn register_unique_type<O: Observer<T> + 'static>(&mut self, observer: &Arc<Mutex<O>>) -> Result<Subscription, RegisterError> {
+        if self.closed {
+            return Err(RegisterError::Closed);
+        }
+        self.prune();
+        if self.entries().any(|(_, s)| s.options.type_id == Some(TypeId::of::<O>())) {
+            return Err(RegisterError::DuplicateType);
+        }
+        Ok(self.register_concrete(observer))
+    }
+    /// Like [`register`](Self::register), but fails when the observable is at capacity.
+    ///
+    /// Dead subscribers are pruned first, so they never count towards the cap.
+    pub fn try_register(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) -> Result<Subscription, RegisterError> {
+        if self.closed {
+            return Err(RegisterError::Closed);
+        }
+        if let Some(capacity) = self.capacity {
+            self.prune();
+            let live = self.live_count();
+            if live >= capacity {
+                return Err(RegisterError::Full { capacity, live });
+            }
+        }
+        Ok(self.register(observer))
+    }
+    /// Registers `observer` to be notified before every subscriber with a lower priority.
+    /// [`register`](Self::register) uses priority 0.
+    pub fn register_with_priority(&mut self, observer: Weak<Mutex<dyn Observer<T>>>, priority: i32) -> Subscription {
+        self.insert(Subscriber::Weak(observer), Options { priority, ..Options::default() })
+    }
+    /// Registers `observer` to be picked by
+    /// [`send_to_weighted_random`](Self::send_to_weighted_random) with a chance
+    /// proportional to `weight`; every other registration weighs 1. A weight of 0 or less
+    /// is never picked. Other sends ignore the weight.
+    pub fn register_weighted(&mut self, weight: f64, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.insert(Subscriber::Weak(observer), Options { weight: Some(weight), ..Options::default() })
+    }
+    /// Like [`register_with_priority`](Self::register_with_priority), but also places
+    /// `observer` among the first or last subscribers to be notified; priority then only
+    /// orders it against subscribers in the same position.
+    pub fn register_positioned(&mut self, observer: Weak<Mutex<dyn Observer<T>>>, priority: i32, position: DeliveryPosition) -> Subscription {
+        self.insert(Subscriber::Weak(observer), Options { priority, position, ..Options::default() })
+    }
+    /// Registers `observer` under `topic`: it then only receives messages sent to that
+    /// topic with [`send_to_topic`](Self::send_to_topic), plus everything sent to all
+    /// subscribers with [`send_to_all`](Self::send_to_all).
+    ///
+    /// Topics are a tag on the subscriber rather than separate lists, so priorities,
+    /// handles and pruning behave the same across topics.
+    pub fn register_on(&mut self, topic: impl Into<String>, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.register_keyed(topic.into(), observer)
+    }
+    fn register_keyed<K: Any + Send + Sync>(&mut self, topic: K, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.insert(Subscriber::Weak(observer), Options { topic: Some(Arc::new(topic)), ..Options::default() })
+    }
+    /// Registers `observer` under a label reported by
+    /// [`subscriber_names`](Self::subscriber_names), for telling subscribers apart while
+    /// debugging. The name has no effect on delivery.
+    pub fn register_named(&mut self, name: impl Into<String>, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.insert(Subscriber::Weak(observer), Options { name: Some(name.into()), ..Options::default() })
+    }
+    /// Registers `observer` in `group`, which [`set_group_enabled`](Self::set_group_enabled)
+    /// can then switch off and on without unregistering anyone.
+    pub fn register_in_group(&mut self, group: GroupId, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.insert(Subscriber::Weak(observer), Options { group, ..Options::default() })
+    }
+    /// Enables or disables every subscriber in `group`, including those registered later.
+    /// Broadcasts skip disabled subscribers without counting them as dropped; sends aimed
+    /// at one subscriber, such as [`send_to_handle`](Self::send_to_handle), still reach
+    /// them. Groups start out enabled.
+    pub fn set_group_enabled(&mut self, group: GroupId, enabled: bool) {
+        if enabled {
+            self.disabled_groups.remove(&group);
+        } else {
+            self.disabled_groups.insert(group);
+        }
+    }
+    pub fn is_group_enabled(&self, group: GroupId) -> bool {
+        !self.disabled_groups.contains(&group)
+    }
+    /// Registers `observer` for a single message: after its first delivery the
+    /// entry is dead and gets removed by the next [`prune`](Self::prune).
+    ///
+    /// Sends only borrow the observable, so they cannot sweep the spent entry themselves:
+    /// until it is pruned it still counts towards [`len`](Self::len) and shows up as dead
+    /// in the `Debug` output, though it is never notified again.
+    pub fn register_once(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.insert(Subscriber::Weak(observer), Options { once: true, ..Options::default() })
+    }
+    /// Registers `observer` while holding a strong reference, so it keeps receiving
+    /// events after every other `Arc` to it is dropped.
+    pub fn register_owned(&mut self, observer: Arc<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.insert(Subscriber::Owned(observer), Options::default())
+    }
+    /// Registers a closure as an observer.
+    ///
+    /// Nothing outside the observable owns the closure, so unlike [`register`](Self::register)
+    /// the observable keeps it alive itself: it is only dropped once the returned
+    /// [`Subscription`] is unregistered, never by [`prune`](Self::prune).
+    pub fn register_fn<F>(&mut self, f: F) -> Subscription
+    where
+        F: FnMut(&T) + Send + 'static,
+    {
+        self.register_owned(Arc::new(Mutex::new(FnObserver::new(f))))
+    }
+    /// Registers `observer` on every observable in `sources`, so one observer hears from
+    /// all of them, and returns a handle that detaches it from all sources when dropped.
+    pub fn merge<'a, I>(sources: I, observer: Weak<Mutex<dyn Observer<T>>>) -> MergedSubscription
+    where
+        I: IntoIterator<Item = &'a mut Observable<T>>,
+        T: 'a,
+    {
+        let subscriptions = sources.into_iter()
+            .map(|source| {
+                let mut subscription = source.register(observer.clone());
+                subscription.set_drop_unsubscribes(true);
+                subscription
+            })
+            .collect();
+        MergedSubscription { subscriptions }
+    }
+    fn insert(&mut self, observer: Subscriber<T>, options: Options) -> Subscription {
+        self.maintain();
+        if self.closed {
+            // a handle to nothing: its slot never exists, so it is stale from the start
+            let cancelled = Arc::new(AtomicBool::new(true));
+            return Subscription { epoch: 0, slot: usize::MAX, cancelled, drop_unsubscribes: false };
+        }
+        if let Some(ratio) = self.auto_prune {
+            let dead = self.len() - self.live_count();
+            if dead as f64 > ratio * self.len() as f64 {
+                self.prune();
+            }
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let rank = options.rank();
+        let entry = Some(Entry { id, observer, cancelled: cancelled.clone(), options, last: None, delivered: AtomicU64::new(0), stale_sends: AtomicUsize::new(0), stats: self.stats.clone(), last_sequence: AtomicU64::new(0) });
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot] = entry;
+                slot
+            }
+            None => {
+                self.slots.push(entry);
+                self.slots.len() - 1
+            }
+        };
+        let epoch = match self.epochs.get_mut(slot) {
+            Some(epoch) => {
+                *epoch = epoch.wrapping_add(1);
+                *epoch
+            }
+            None => {
+                self.epochs.push(0);
+                0
+            }
+        };
+        let slots = &self.slots;
+        let i = self.order.partition_point(|&j| slots[j].as_ref().is_some_and(|s| s.options.rank() <= rank));
+        self.order.insert(i, slot);
+        Subscription { epoch, slot, cancelled, drop_unsubscribes: false }
+    }
+    // Occupied slots in delivery order, with their index.
+    fn entries(&self) -> impl Iterator<Item = (usize, &Entry<T>)> {
+        self.order.iter().filter_map(|&i| Some((i, self.slots[i].as_ref()?)))
+    }
+    // The entries a broadcast goes to: those outside disabled groups.
+    fn recipients(&self) -> impl Iterator<Item = (usize, &Entry<T>)> {
+        self.entries().filter(|(_, s)| self.is_group_enabled(s.options.group))
+    }
+    fn entry(&self, i: usize) -> Option<&Entry<T>> {
+        self.slots.get(i)?.as_ref()
+    }
+    // Empties the slot of every entry for which `remove` returns true, returning how many.
+    fn remove_where(&mut self, mut remove: impl FnMut(&Entry<T>) -> bool) -> usize {
+        let (slots, free) = (&mut self.slots, &mut self.free);
+        let before = self.order.len();
+        self.order.retain(|&i| {
+            let removed = slots[i].as_ref().is_some_and(&mut remove);
+            if removed {
+                slots[i] = None;
+                free.push(i);
+            }
+            !removed
+        });
+        before - self.order.len()
+    }
+    fn remove_slot(&mut self, i: usize) {
+        self.slots[i] = None;
+        self.free.push(i);
+        self.order.retain(|&j| j != i);
+    }
+    /// Removes the registration behind `subscription`, returning whether it was still present.
+    pub fn unregister(&mut self, subscription: Subscription) -> bool {
+        self.position(&subscription)
+            .map(|i| self.remove_slot(i))
+            .is_some()
+    }
+    /// Points the registration behind `subscription` at `observer`, keeping its slot, its
+    /// place in delivery order and its options (priority, topic, name, one-shot). The
+    /// handle stays valid and now refers to the new observer. The new observer's type is
+    /// unknown, so it does not count towards
+    /// [`register_unique_type`](Self::register_unique_type).
+    ///
+    /// What [`send_if_changed`](Self::send_if_changed) remembers is reset, so the new
+    /// observer receives the next message whatever the old one saw, and so are its
+    /// delivery count and last sequence number in [`snapshot`](Self::snapshot).
+    pub fn replace(&mut self, subscription: &Subscription, observer: Weak<Mutex<dyn Observer<T>>>) -> Result<(), ReplaceError> {
+        if self.is_stale(subscription) {
+            return Err(ReplaceError::StaleHandle);
+        }
+        let i = self.position(subscription).ok_or(ReplaceError::Removed)?;
+        let entry = self.slots[i].as_mut().ok_or(ReplaceError::Removed)?;
+        entry.observer = Subscriber::Weak(observer);
+        entry.options.type_id = None;
+        entry.last = None;
+        entry.delivered = AtomicU64::new(0);
+        entry.stale_sends = AtomicUsize::new(0);
+        entry.last_sequence = AtomicU64::new(0);
+        Ok(())
+    }
+    /// Whether `observer` is a live subscriber, compared by pointer. Dead and
+    /// unsubscribed entries do not count.
+    pub fn contains(&self, observer: &Weak<Mutex<dyn Observer<T>>>) -> bool {
+        self.entries().any(|(_, s)| s.observer.ptr_eq(observer) && s.upgrade().is_some())
+    }
+    /// Like [`contains`](Self::contains), for a strong reference.
+    pub fn contains_arc(&self, observer: &Arc<Mutex<dyn Observer<T>>>) -> bool {
+        self.contains(&Arc::downgrade(observer))
+    }
+    /// Removes the first registration of `observer`, returning whether one was found.
+    pub fn unregister_observer(&mut self, observer: &Weak<Mutex<dyn Observer<T>>>) -> bool {
+        let found = self.entries()
+            .find(|(_, s)| s.observer.ptr_eq(observer))
+            .map(|(i, _)| i);
+        found.map(|i| self.remove_slot(i)).is_some()
+    }
+    /// Removes every subscriber, dropping owned observers, and returns how many entries
+    /// were removed. Outstanding [`Subscription`]s no longer match anything afterwards.
+    pub fn clear(&mut self) -> usize {
+        let removed = self.order.len();
+        self.slots.clear();
+        self.free.clear();
+        self.order.clear();
+        removed
+    }
+    /// Moves every live subscriber into `other`, with its priority, position, topic, name
+    /// and group, and returns their new handles in delivery order. This observable is left
+    /// empty and its old handles stale; dead entries are dropped on the way.
+    ///
+    /// The moved subscribers join `other`'s delivery order by priority and position, after
+    /// the subscribers it already had that rank the same.
+    pub fn merge_into(&mut self, other: &mut Observable<T>) -> Vec<Subscription> {
+        let order = std::mem::take(&mut self.order);
+        let moved = order.into_iter()
+            .filter_map(|i| self.slots[i].take())
+            .filter(|s| s.upgrade().is_some())
+            .map(|s| other.insert(s.observer, s.options))
+            .collect();
+        self.clear();
+        moved
+    }
+    /// Splits the live subscribers between two new observables: those for which `f`
+    /// returns `true` go to the first, the rest to the second, each keeping its priority,
+    /// position, topic, name and group. Dead subscribers are dropped, and poisoned ones,
+    /// which `f` cannot look at, go to the second. Old handles are stale in both halves.
+    ///
+    /// The halves keep this observable's poison policy and pruning settings, and start with
+    /// no capacity limit, logger, middleware, delivery order, dead-letter handler or
+    /// statistics.
+    pub fn split<F>(mut self, mut f: F) -> (Observable<T>, Observable<T>)
+    where
+        F: FnMut(&mut dyn Observer<T>) -> bool,
+    {
+        let halves = [(); 2].map(|_| {
+            let mut half = Observable::new();
+            half.poison_policy = self.poison_policy;
+            half.auto_prune = self.auto_prune;
+            half.prune_every = self.prune_every;
+            half
+        });
+        let [mut first, mut second] = halves;
+        for i in std::mem::take(&mut self.order) {
+            let Some(s) = self.slots[i].take() else {
+                continue;
+            };
+            let Some(o) = s.upgrade() else {
+                continue;
+            };
+            let matched = lock(&o, self.poison_policy, &s.cancelled).is_ok_and(|mut o| f(&mut *o));
+            drop(o);
+            if matched { &mut first } else { &mut second }.insert(s.observer, s.options);
+        }
+        (first, second)
+    }
+    /// Wraps this observable for sharing: unlike [`clone`](Clone::clone), which copies the
+    /// subscriber list, every clone of the returned `Arc` registers on and sends to the
+    /// one list.
+    pub fn shared_handle(self) -> Arc<Mutex<Observable<T>>> {
+        Arc::new(Mutex::new(self))
+    }
+    /// Sends `final_message` to every subscriber as with [`send_to_all`](Self::send_to_all),
+    /// then [`clear`](Self::clear)s them and closes the observable for good, returning how
+    /// many received the final message. Messages held back by a [`pause`](Self::pause) are
+    /// delivered first.
+    ///
+    /// Once closed, [`try_register`](Self::try_register) and
+    /// [`register_unique_type`](Self::register_unique_type) fail with
+    /// [`RegisterError::Closed`], the other `register` methods drop the observer and return
+    /// a handle that matches nothing, and sends reach nobody.
+    pub fn shutdown(&mut self, final_message: &T) -> usize
+    where
+        T: Sized + 'static,
+    {
+        self.resume();
+        let delivered = self.send_to_all(final_message);
+        self.clear();
+        self.closed = true;
+        delivered
+    }
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+    /// Removes every subscriber and returns weak references to the live ones, in delivery
+    /// order, for handing over to another observable with
+    /// [`register_many`](Self::register_many).
+    ///
+    /// Priorities, topics and one-shot settings are not carried over. Observers held only
+    /// by this observable ([`register_owned`](Self::register_owned),
+    /// [`register_fn`](Self::register_fn)) are dropped with it, so their references come
+    /// back dead.
+    pub fn drain(&mut self) -> Vec<Weak<Mutex<dyn Observer<T>>>> {
+        let drained = self.entries()
+            .filter_map(|(_, s)| s.upgrade())
+            .map(|o| Arc::downgrade(&o))
+            .collect();
+        self.clear();
+        drained
+    }
+    /// Registers each of `observers` as with [`register`](Self::register), returning the
+    /// handles in the same order.
+    pub fn register_many(&mut self, observers: Vec<Weak<Mutex<dyn Observer<T>>>>) -> Vec<Subscription> {
+        self.register_all(observers)
+    }
+    /// Like [`register_many`](Self::register_many), but takes any iterator. The
+    /// [`Extend`] impl does the same and discards the handles.
+    pub fn register_all<I>(&mut self, observers: I) -> Vec<Subscription>
+    where
+        I: IntoIterator<Item = Weak<Mutex<dyn Observer<T>>>>,
+    {
+        observers.into_iter().map(|o| self.register(o)).collect()
+    }
+    /// Index, name and liveness of every subscriber registered with
+    /// [`register_named`](Self::register_named), in delivery order. Dead entries are listed
+    /// until they are pruned; a poisoned observer still counts as alive.
+    pub fn subscriber_names(&self) -> Vec<(usize, String, bool)> {
+        self.entries()
+            .filter_map(|(i, s)| s.options.name.clone().map(|name| (i, name, s.upgrade().is_some())))
+            .collect()
+    }
+    /// Index and `(strong_count, weak_count)` of every subscriber's `Arc`, in delivery order,
+    /// for tracking down who keeps an observer alive; `None` for a dead or unsubscribed
+    /// entry. An observer held only by the observable has a strong count of 1 if it was
+    /// registered owned, and counts the observable's own `Weak` otherwise.
+    pub fn subscriber_refcounts(&self) -> Vec<(usize, Option<(usize, usize)>)> {
+        self.entries()
+            .map(|(i, s)| {
+                let counts = s.observer.counts();
+                let alive = counts.0 > 0 && !s.cancelled.load(Ordering::Acquire);
+                (i, alive.then_some(counts))
+            })
+            .collect()
+    }
+    /// Strong references to every live subscriber, in delivery order, for working with the
+    /// observers without borrowing the observable. They keep the observers alive until
+    /// dropped, so [`prune`](Self::prune) will not remove them in the meantime.
+    pub fn upgrade_all(&self) -> Vec<Arc<Mutex<dyn Observer<T>>>> {
+        self.entries().filter_map(|(_, s)| s.upgrade()).collect()
+    }
+    /// Number of subscribers that are still alive.
+    pub fn live_count(&self) -> usize {
+        self.entries().filter(|(_, s)| s.upgrade().is_some()).count()
+    }
+    /// Number of stored entries, including dead ones that have not been pruned yet.
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+    /// Whether no live subscriber is attached.
+    pub fn is_empty(&self) -> bool {
+        self.live_count() == 0
+    }
+    /// Removes every subscriber that has been dropped or unsubscribed, returning how many were removed.
+    ///
+    /// `send_to_all` only borrows the observable, so dead entries are never
+    /// cleaned up as a side effect of sending; call this periodically instead, or have it
+    /// scheduled with [`set_auto_prune_after`](Self::set_auto_prune_after).
+    pub fn prune(&mut self) -> usize {
+        *self.sends_since_prune.get_mut() = 0;
+        let mut max_stale_sends = 0;
+        let pruned = self.remove_where(|s| {
+            let dead = s.upgrade().is_none();
+            if dead {
+                max_stale_sends = max_stale_sends.max(s.stale_sends.load(Ordering::Relaxed));
+            }
+            dead
+        });
+        self.last_prune = PruneStats { pruned, max_stale_sends };
+        pruned
+    }
+    /// What the latest [`prune`](Self::prune), explicit or automatic, removed, for tuning
+    /// [`set_auto_prune_after`](Self::set_auto_prune_after). A stale send is one that
+    /// reached an entry whose observer was dropped or unsubscribed; sends that skip the
+    /// entry, such as one to another topic or a disabled group, do not count. All zero
+    /// before the first prune.
+    pub fn last_prune_stats(&self) -> PruneStats {
+        self.last_prune
+    }
+    /// Sends `message` to every live subscriber and [`prune`](Self::prune)s the dead ones
+    /// in the same pass over the subscribers, returning how many were notified. A one-shot
+    /// subscriber, or one that unsubscribes itself, is removed right after its delivery.
+    ///
+    /// Nothing is compacted: survivors keep their indices, so index-based sends such as
+    /// [`send_to`](Self::send_to) still reach them, and the removed slots are handed to
+    /// later registrations as after a `prune`. Unlike `send_to_all`, the message is not
+    /// held back by a [`pause`](Self::pause), and neither the delivery order strategy nor
+    /// the dead-letter handler applies.
+    pub fn send_and_prune(&mut self, message: &T) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let (mut delivered, mut dropped, mut max_stale_sends) = (0, 0, 0);
+        let (slots, free) = (&mut self.slots, &mut self.free);
+        let before = self.order.len();
+        self.order.retain(|&i| {
+            let Some(s) = slots[i].as_ref() else {
+                return true;
+            };
+            if !self.disabled_groups.contains(&s.options.group) {
+                match s.deliver(message, self.poison_policy) {
+                    DeliveryResult::Delivered => delivered += 1,
+                    _ => dropped += 1,
+                }
+            }
+            let dead = s.upgrade().is_none();
+            if dead {
+                max_stale_sends = max_stale_sends.max(s.stale_sends.load(Ordering::Relaxed));
+                slots[i] = None;
+                free.push(i);
+            }
+            !dead
+        });
+        let pruned = before - self.order.len();
+        self.last_prune = PruneStats { pruned, max_stale_sends };
+        *self.sends_since_prune.get_mut() = 0;
+        let results = std::iter::repeat_n(DeliveryResult::Delivered, delivered)
+            .chain(std::iter::repeat_n(DeliveryResult::Dropped, dropped));
+        self.record(1, results)
+    }
+    /// Has dead subscribers pruned once every `sends` calls to
+    /// [`send_to_all`](Self::send_to_all), so that a long-lived observable stays tidy
+    /// without explicit [`prune`](Self::prune) calls; 0, the default, turns this off.
+    ///
+    /// Sends only borrow the observable, so they just count: the prune that is due runs
+    /// at the start of the next call that takes `&mut self` and touches the subscribers,
+    /// any `register` method or a `&mut self` send such as
+    /// [`send_to_next`](Self::send_to_next) or [`send_dedup`](Self::send_dedup).
+    pub fn set_auto_prune_after(&mut self, sends: usize) {
+        self.prune_every = sends;
+    }
+    // Runs the prune `set_auto_prune_after` asked for, if one is due.
+    fn maintain(&mut self) {
+        if self.prune_every > 0 && *self.sends_since_prune.get_mut() >= self.prune_every {
+            self.prune();
+        }
+    }
+    /// Keeps only the subscribers for which `f` returns `true`, like [`Vec::retain`].
+    ///
+    /// Each observer is locked while `f` inspects it; dead and poisoned subscribers are
+    /// removed without calling `f`.
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut dyn Observer<T>) -> bool,
+    {
+        self.remove_where(|s| match s.upgrade() {
+            Some(o) => !o.lock().is_ok_and(|mut o| f(&mut *o)),
+            None => true,
+        });
+    }
+    /// Removes the subscribers for which `f` returns `true` and returns weak references to
+    /// them in delivery order, for registering elsewhere; the opposite of
+    /// [`retain`](Self::retain). Dead subscribers are removed too but not returned.
+    /// Poisoned ones cannot be inspected and stay.
+    ///
+    /// As with [`drain`](Self::drain), settings are not carried over, and the references
+    /// to observers held only by this observable come back dead.
+    pub fn drain_filter<F>(&mut self, mut f: F) -> Vec<Weak<Mutex<dyn Observer<T>>>>
+    where
+        F: FnMut(&mut dyn Observer<T>) -> bool,
+    {
+        let mut drained = Vec::new();
+        self.remove_where(|s| match s.upgrade() {
+            Some(o) => {
+                let matched = o.lock().is_ok_and(|mut o| f(&mut *o));
+                if matched {
+                    drained.push(Arc::downgrade(&o));
+                }
+                matched
+            }
+            None => true,
+        });
+        drained
+    }
+    /// Sends `message` to every live subscriber, returning how many were notified.
+    ///
+    /// A call made from inside one of this observable's own `notify` calls on the same
+    /// thread is ignored and returns 0, see [`try_send_to_all`](Self::try_send_to_all).
+    pub fn send_to_all(&self, message: &T) -> usize {
+        self.try_send_to_all(message).unwrap_or(0)
+    }
+    /// Sends `message` to every live subscriber in registration order, returning how many
+    /// were notified.
+    ///
+    /// Unlike [`send_to_all`](Self::send_to_all), which follows subscriber order and so
+    /// puts higher priorities first, this ignores priorities and does not depend on how
+    /// subscribers are stored: the earliest surviving registration is always notified
+    /// first. Nested sends are ignored as with `send_to_all`.
+    pub fn send_to_all_ordered(&self, message: &T) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let mut ordered: Vec<_> = self.recipients().map(|(_, s)| s).collect();
+        // ids are handed out in increasing order and never reused
+        ordered.sort_by_key(|s| s.id);
+        self.record(1, ordered.into_iter().map(|s| s.deliver(message, self.poison_policy)))
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but subscribers of equal priority are
+    /// notified most recent registration first, for LIFO teardown. Higher priorities and
+    /// [`DeliveryPosition`]s still apply. Nested sends are ignored as with `send_to_all`.
+    pub fn send_to_all_reverse(&self, message: &T) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let entries: Vec<_> = self.recipients().map(|(_, s)| s).collect();
+        let results = entries.chunk_by(|a, b| a.options.rank() == b.options.rank())
+            .flat_map(|ties| ties.iter().rev())
+            .map(|s| s.deliver(message, self.poison_policy));
+        self.record(1, results)
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but clones `message` for each observer whose
+    /// [`wants_owned`](Observer::wants_owned) returns `true` and hands it over through
+    /// [`notify_owned`](Observer::notify_owned). Observers that only read it are lent the
+    /// original, so there are only as many clones as owners. Nested sends are ignored as
+    /// with `send_to_all`.
+    pub fn send_to_all_cloned(&self, message: &T) -> usize
+    where
+        T: Clone,
+    {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let results = self.recipients()
+            .map(|(_, s)| match s.deliver_with(self.poison_policy, |o| {
+                if o.wants_owned() {
+                    o.notify_owned(message.clone());
+                    NotifyAction::Keep
+                } else {
+                    o.notify_action(message)
+                }
+            }) {
+                Ok(action) => {
+                    s.apply(action);
+                    DeliveryResult::Delivered
+                }
+                Err(r) => r,
+            });
+        self.record(1, results)
+    }
+    /// Queues `message` and, unless this thread is already sending on this observable,
+    /// delivers the queue with [`send_to_all`](Self::send_to_all) until it is empty,
+    /// returning the number of deliveries made.
+    ///
+    /// This is how an observer emits a follow-up event from `notify`: the nested call
+    /// only queues the message (and returns 0), and the outer `enqueue` delivers it once
+    /// the current fan-out is complete, so every subscriber sees the events in order.
+    /// Messages queued during a send other than `enqueue` wait for the next call.
+    pub fn enqueue(&self, message: T) -> usize
+    where
+        T: Sized + Send + 'static,
+    {
+        let queue = || self.queue.lock().unwrap_or_else(PoisonError::into_inner);
+        queue().push_back(Box::new(message));
+        if DispatchGuard::enter(self).is_err() {
+            return 0;
+        }
+        let mut delivered = 0;
+        loop {
+            // popped in its own statement so that the lock is released before delivering
+            // and observers can queue more
+            let next = queue().pop_front();
+            let Some(message) = next else {
+                return delivered;
+            };
+            if let Ok(message) = message.downcast::<T>() {
+                delivered += self.send_to_all(&message);
+            }
+        }
+    }
+    /// Suspends [`send_to_all`](Self::send_to_all), including its
+    /// [`try_send_to_all`](Self::try_send_to_all),
+    /// [`send_to_all_detailed`](Self::send_to_all_detailed) and
+    /// [`send_to_all_complete`](Self::send_to_all_complete) forms: until
+    /// [`resume`](Self::resume), they keep a clone of each message and notify nobody. Other
+    /// sends still deliver, since what they do cannot be replayed later.
+    pub fn pause(&mut self)
+    where
+        T: Clone + Send + 'static,
+    {
+        self.pause = Some(|message| Box::new(message.clone()));
+    }
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_some()
+    }
+    /// Ends a [`pause`](Self::pause), sending every message kept meanwhile in the order
+    /// they were sent, and returns the number of deliveries made.
+    pub fn resume(&mut self) -> usize
+    where
+        T: Sized + 'static,
+    {
+        self.pause = None;
+        let paused = std::mem::take(self.paused.get_mut().unwrap_or_else(PoisonError::into_inner));
+        paused.into_iter()
+            .filter_map(|message| message.downcast::<T>().ok())
+            .map(|message| self.send_to_all(&message))
+            .sum()
+    }
+    /// Stamps a new [`EventContext`]; every call gets a higher sequence number than the last.
+    pub fn next_context(&self) -> EventContext {
+        EventContext {
+            sequence: self.sequence.fetch_add(1, Ordering::Relaxed),
+            timestamp: Instant::now(),
+        }
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but hands `ctx` to every observer through
+    /// [`Observer::notify_ctx`]. Contexts usually come from [`next_context`](Self::next_context).
+    pub fn send_to_all_with_ctx(&self, message: &T, ctx: &EventContext) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let results = self.recipients()
+            .map(|(_, s)| match s.deliver_with(self.poison_policy, |o| o.notify_ctx(message, ctx)) {
+                Ok(()) => DeliveryResult::Delivered,
+                Err(r) => r,
+            });
+        self.record(1, results)
+    }
+    /// Notifies at most `budget` live subscribers, starting where the previous budgeted
+    /// send stopped and wrapping around, and returns how many were notified.
+    ///
+    /// Dead entries are skipped without using up the budget. The cursor is a position in
+    /// delivery order, so removing or inserting subscribers between calls can make it
+    /// skip or repeat one.
+    pub fn send_to_all_budgeted(&mut self, message: &T, budget: usize) -> usize {
+        self.log(message);
+        self.round_robin(message, budget).len()
+    }
+    /// Delivers `message` to exactly one live subscriber, taking turns in delivery order,
+    /// and returns the index of the one notified, or `None` if none is live.
+    ///
+    /// This turns the broadcast into work distribution over a pool of observers. It
+    /// shares its cursor with [`send_to_all_budgeted`](Self::send_to_all_budgeted) and
+    /// skips dead entries the same way.
+    pub fn send_to_next(&mut self, message: &T) -> Option<usize> {
+        self.round_robin(message, 1).pop()
+    }
+    // Notifies up to `budget` live, enabled subscribers from the cursor on, moving the cursor past
+    // every entry tried, and returns the indices of those notified.
+    fn round_robin(&mut self, message: &T, budget: usize) -> Vec<usize> {
+        self.maintain();
+        let len = self.order.len();
+        let mut tried = 0;
+        let mut results = Vec::new();
+        let mut delivered = Vec::new();
+        while tried < len && delivered.len() < budget {
+            let i = self.order[(self.cursor + tried) % len];
+            tried += 1;
+            let result = match &self.slots[i] {
+                // disabled groups are passed over without being tried
+                Some(s) if !self.is_group_enabled(s.options.group) => continue,
+                Some(s) => s.deliver(message, self.poison_policy),
+                None => DeliveryResult::Dropped,
+            };
+            if result == DeliveryResult::Delivered {
+                delivered.push(i);
+            }
+            results.push(result);
+        }
+        if len > 0 {
+            self.cursor = (self.cursor + tried) % len;
+        }
+        self.record(1, results);
+        delivered
+    }
+    /// Sends `message` to all subscribers unless `id` is among the last ids passed here,
+    /// returning whether it was sent. Replaying a message from a producer that may repeat
+    /// itself is then delivered at most once, as long as the repeat arrives within the
+    /// window set by [`set_dedup_window`](Self::set_dedup_window).
+    pub fn send_dedup(&mut self, id: impl Into<EventId>, message: &T) -> bool {
+        self.maintain();
+        let id = id.into();
+        if self.dedup_window == 0 {
+            self.send_to_all(message);
+            return true;
+        }
+        if self.recent_ids.contains(&id) {
+            return false;
+        }
+        if self.recent_ids.len() == self.dedup_window {
+            self.recent_ids.pop_front();
+        }
+        self.recent_ids.push_back(id);
+        self.send_to_all(message);
+        true
+    }
+    /// How many of the most recent ids [`send_dedup`](Self::send_dedup) remembers, 64 by
+    /// default. The ids are searched linearly, so keep the window modest; 0 turns
+    /// deduplication off.
+    pub fn set_dedup_window(&mut self, window: usize) {
+        self.dedup_window = window;
+        let excess = self.recent_ids.len().saturating_sub(window);
+        self.recent_ids.drain(..excess);
+    }
+    /// Sends `message` to every subscriber whose last message from this method differs
+    /// from it, returning how many were notified. A subscriber that has not received
+    /// anything through this method yet is always notified.
+    ///
+    /// The last value is remembered per registration, so a subscriber taking over a
+    /// vacated slot starts afresh. It is stored type-erased, which is where the
+    /// `Send + Sync + 'static` bounds come from.
+    ///
+    /// The message is logged unless every subscriber already holds it, so a repeated
+    /// value does not flood the log.
+    pub fn send_if_changed(&mut self, message: &T) -> usize
+    where
+        T: PartialEq + Clone + Send + Sync + 'static,
+    {
+        self.maintain();
+        let mut live = self.order.iter()
+            .filter_map(|&i| self.slots[i].as_ref().filter(|s| !self.disabled_groups.contains(&s.options.group)))
+            .peekable();
+        let unchanged = live.peek().is_some()
+            && live.all(|s| s.last.as_ref().and_then(|last| last.downcast_ref::<T>()) == Some(message));
+        if !unchanged {
+            self.log(message);
+        }
+        let results: Vec<_> = self.order.iter()
+            .filter_map(|&i| {
+                let s = self.slots[i].as_mut().filter(|s| !self.disabled_groups.contains(&s.options.group))?;
+                if s.last.as_ref().and_then(|last| last.downcast_ref::<T>()) == Some(message) {
+                    return None;
+                }
+                let result = s.deliver(message, self.poison_policy);
+                if result == DeliveryResult::Delivered {
+                    s.last = Some(Arc::new(message.clone()));
+                }
+                Some(result)
+            })
+            .collect();
+        self.record(1, results)
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but reports a nested send instead of ignoring it.
+    ///
+    /// Letting the nested send through would try to lock the observer that is running
+    /// it, which deadlocks, and could recurse without bound.
+    pub fn try_send_to_all(&self, message: &T) -> Result<usize, ReentrantSend> {
+        self.dispatch(message, |_| {})
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but fails with [`NoSubscribers`] when nobody
+    /// was notified, so that a producer can buffer or log a message that would otherwise be
+    /// lost. An ignored nested send fails the same way.
+    pub fn send_or_err(&self, message: &T) -> Result<usize, NoSubscribers> {
+        match self.send_to_all(message) {
+            0 => Err(NoSubscribers),
+            delivered => Ok(delivered),
+        }
+    }
+    /// Sends `message` to every subscriber and returns whether all live ones were notified.
+    ///
+    /// Dropped or unsubscribed entries do not count against completeness; a poisoned
+    /// observer or a nested send (see [`try_send_to_all`](Self::try_send_to_all)) does.
+    pub fn send_to_all_complete(&self, message: &T) -> bool {
+        self.try_send_to_all_detailed(message)
+            .is_ok_and(|results| !results.contains(&DeliveryResult::Poisoned))
+    }
+    /// Counters accumulated over every send since the observable was created.
+    pub fn stats(&self) -> ObservableStats {
+        ObservableStats {
+            total_sent: self.stats.sent.load(Ordering::Relaxed),
+            total_delivered: self.stats.delivered.load(Ordering::Relaxed),
+            total_dropped: self.stats.dropped.load(Ordering::Relaxed),
+        }
+    }
+    // Accounts for `sent` dispatched messages and the outcome of each delivery attempt,
+    // returning how many succeeded.
+    fn record(&self, sent: usize, results: impl IntoIterator<Item = DeliveryResult>) -> usize {
+        self.stats.record(sent, results)
+    }
+    /// Sends `message` to every subscriber, returning one result per entry in subscriber order.
+    /// A nested send (see [`send_to_all`](Self::send_to_all)) returns an empty vector.
+    pub fn send_to_all_detailed(&self, message: &T) -> Vec<DeliveryResult> {
+        self.try_send_to_all_detailed(message).unwrap_or_default()
+    }
+    /// Like [`send_to_all_detailed`](Self::send_to_all_detailed), but hands each
+    /// subscriber's index and result to `on_result` as soon as it is known instead of
+    /// collecting them, and returns how many were notified. Nested sends are ignored as
+    /// with `send_to_all` and report nothing.
+    pub fn send_to_all_with<F>(&self, message: &T, mut on_result: F) -> usize
+    where
+        F: FnMut(usize, DeliveryResult),
+    {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let results = self.recipients()
+            .map(|(i, s)| {
+                let result = s.deliver(message, self.poison_policy);
+                on_result(i, result);
+                result
+            });
+        self.record(1, results)
+    }
+    fn try_send_to_all_detailed(&self, message: &T) -> Result<Vec<DeliveryResult>, ReentrantSend> {
+        let mut results = Vec::new();
+        self.dispatch(message, |result| results.push(result))?;
+        Ok(results)
+    }
+    // The delivery loop behind `send_to_all` and its detailed forms. Each result is handed
+    // to `each` as it comes rather than collected, so the plain send allocates nothing.
+    fn dispatch(&self, message: &T, mut each: impl FnMut(DeliveryResult)) -> Result<usize, ReentrantSend> {
+        let _guard = DispatchGuard::enter(self)?;
+        if let Some(copy) = self.pause {
+            self.paused.lock().unwrap_or_else(PoisonError::into_inner).push(copy(message));
+            return Ok(0);
+        }
+        self.log(message);
+        self.sends_since_prune.fetch_add(1, Ordering::Relaxed);
+        self.trace_start(self.len());
+        let started = self.latency_start();
+        let visit = |(i, s): (usize, &Entry<T>)| {
+            let result = s.deliver(message, self.poison_policy);
+            self.trace_delivery(i, result);
+            self.latency_delivery(&started, result);
+            each(result);
+            result
+        };
+        let delivered = match &self.delivery_order {
+            None => self.record(1, self.recipients().map(visit)),
+            Some(order) => {
+                let recipients: Vec<_> = self.recipients().collect();
+                let ordered = order.order(recipients.len()).into_iter().filter_map(|p| recipients.get(p).copied());
+                self.record(1, ordered.map(visit))
+            }
+        };
+        if delivered == 0 {
+            self.send_dead_letter(message);
+        }
+        self.trace_end(delivered);
+        Ok(delivered)
+    }
+    /// Has `handler` notified of every [`send_to_all`](Self::send_to_all), or other send
+    /// built on it, that reaches no subscriber, instead of the message being lost; it
+    /// replaces any previous handler. The handler is not a subscriber: it is left out of
+    /// [`len`](Self::len), the statistics and every other send. Sends that are ignored as
+    /// nested, or held back by a [`pause`](Self::pause), are not dead letters.
+    pub fn set_dead_letter(&mut self, handler: Weak<Mutex<dyn Observer<T>>>) {
+        self.dead_letter = Some(handler);
+    }
+    fn send_dead_letter(&self, message: &T) {
+        let Some(handler) = self.dead_letter.as_ref().and_then(Weak::upgrade) else {
+            return;
+        };
+        // a poisoned handler is skipped, whatever the policy for subscribers
+        if let Ok(mut handler) = handler.lock() {
+            handler.notify(message);
+        };
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but delivers through
+    /// [`Observer::notify_ack`] and reports which subscribers acknowledged the message, for
+    /// an at-least-once layer that retries the rest. A nested send is ignored and reports
+    /// nobody.
+    pub fn send_to_all_tracked(&self, message: &T) -> AckReport {
+        let mut report = AckReport::default();
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return report;
+        };
+        self.log(message);
+        let results = self.recipients()
+            .map(|(i, s)| match s.deliver_with(self.poison_policy, |o| o.notify_ack(message)) {
+                Ok(acked) => {
+                    if acked { &mut report.acked } else { &mut report.nacked }.push(i);
+                    DeliveryResult::Delivered
+                }
+                Err(r) => {
+                    report.failed.push(i);
+                    r
+                }
+            });
+        self.record(1, results);
+        report
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but delivers through
+    /// [`Observer::notify_indexed`], numbering the subscribers that are alive when the send
+    /// starts from 0 in delivery order, out of their total. One that dies or turns out
+    /// poisoned mid-send still takes up its index, so the others' stay fixed.
+    pub fn send_to_all_indexed(&self, message: &T) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let (live, dead): (Vec<_>, Vec<_>) = self.recipients().partition(|(_, s)| s.upgrade().is_some());
+        let total = live.len();
+        let results = live.into_iter()
+            .enumerate()
+            .map(|(index, (_, s))| {
+                s.deliver_with(self.poison_policy, |o| o.notify_indexed(message, index, total))
+                    .map_or_else(|r| r, |()| DeliveryResult::Delivered)
+            })
+            .chain(dead.into_iter().map(|(_, s)| s.stale()));
+        self.record(1, results)
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but returns a strong reference to every
+    /// subscriber it notified, in delivery order, so their state can be read after the send
+    /// while they are sure to still be alive. Each observer's lock is released before its
+    /// `Arc` is handed back. A nested send returns an empty vector.
+    pub fn send_to_all_returning(&self, message: &T) -> Vec<Arc<Mutex<dyn Observer<T>>>> {
+        let mut notified = Vec::new();
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return notified;
+        };
+        self.log(message);
+        let results = self.recipients()
+            .map(|(_, s)| {
+                let Some(observer) = s.upgrade() else {
+                    return s.stale();
+                };
+                let delivered = match lock(&observer, self.poison_policy, &s.cancelled) {
+                    Ok(mut locked) => s.notify(&mut *locked, message),
+                    Err(r) => return r,
+                };
+                if !delivered {
+                    return DeliveryResult::Dropped;
+                }
+                notified.push(observer);
+                DeliveryResult::Delivered
+            });
+        self.record(1, results);
+        notified
+    }
+    /// Sends `message` to every live subscriber through [`Observer::handle`] and returns
+    /// each one's verdict next to its index, in delivery order. A nested send (see
+    /// [`send_to_all`](Self::send_to_all)) returns an empty vector.
+    pub fn send_collect(&self, message: &T) -> Vec<(usize, Result<(), String>)> {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return Vec::new();
+        };
+        let mut verdicts = Vec::new();
+        let deliveries = self.recipients()
+            .map(|(i, s)| match s.deliver_with(self.poison_policy, |o| o.handle(message)) {
+                Ok(verdict) => {
+                    verdicts.push((i, verdict));
+                    (i, DeliveryResult::Delivered)
+                }
+                Err(r) => (i, r),
+            });
+        self.broadcast(message, deliveries);
+        verdicts
+    }
+    // What `dispatch` does around the deliveries, for broadcasts that notify through a
+    // hook of their own: logs `message`, then traces and times each of `deliveries`, the
+    // subscriber's index and result, as it is made, and returns how many were delivered.
+    fn broadcast(&self, message: &T, deliveries: impl Iterator<Item = (usize, DeliveryResult)>) -> usize {
+        self.log(message);
+        self.trace_start(self.len());
+        let started = self.latency_start();
+        let delivered = self.record(1, deliveries.map(|(i, result)| {
+            self.trace_delivery(i, result);
+            self.latency_delivery(&started, result);
+            result
+        }));
+        self.trace_end(delivered);
+        delivered
+    }
+    /// Sends each live subscriber its own message, built by `factory` from the
+    /// subscriber's index, and returns how many were notified.
+    ///
+    /// Subscribers are visited in delivery order, as by [`send_to_all`](Self::send_to_all).
+    /// `factory` runs once per subscriber about to be notified, with its lock held; dead,
+    /// poisoned and spent subscribers never reach it. Nested sends are ignored as with
+    /// `send_to_all`.
+    pub fn send_with<F>(&self, factory: F) -> usize
+    where
+        T: Sized,
+        F: Fn(usize) -> T,
+    {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        let results = self.recipients()
+            .map(|(i, s)| match s.deliver_with(self.poison_policy, |o| o.notify_action(&factory(i))) {
+                Ok(action) => {
+                    s.apply(action);
+                    DeliveryResult::Delivered
+                }
+                Err(r) => r,
+            });
+        self.record(1, results)
+    }
+    /// Asks every live subscriber `message` through [`Observer::query`] and collects the
+    /// answers of type `A`, in delivery order. Observers that answer `None` or with
+    /// another type are left out. A nested send (see [`send_to_all`](Self::send_to_all))
+    /// returns an empty vector.
+    pub fn query_all<A: 'static>(&self, message: &T) -> Vec<A> {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return Vec::new();
+        };
+        let mut answers = Vec::new();
+        let deliveries = self.recipients()
+            .map(|(i, s)| match s.deliver_with(self.poison_policy, |o| o.query(message)) {
+                Ok(answer) => {
+                    answers.extend(answer.and_then(|a| a.downcast().ok()).map(|a: Box<A>| *a));
+                    (i, DeliveryResult::Delivered)
+                }
+                Err(r) => (i, r),
+            });
+        self.broadcast(message, deliveries);
+        answers
+    }
+    /// Request/response over the subscribers: sends `message` to every live one through
+    /// [`Observer::notify_with_reply`], then collects the replies of type `R` in the order
+    /// they arrive. Waits until every notified observer has replied, every [`Reply`] has
+    /// been dropped, or `timeout` has passed since the send started, whichever comes first.
+    /// A nested send (see [`send_to_all`](Self::send_to_all)) returns an empty vector.
+    pub fn send_collect_replies<R: 'static>(&self, message: &T, timeout: Duration) -> Vec<R> {
+        let deadline = Instant::now().checked_add(timeout);
+        let (sender, receiver) = mpsc::channel();
+        let notified = {
+            let Ok(_guard) = DispatchGuard::enter(self) else {
+                return Vec::new();
+            };
+            let reply = Reply { sender };
+            let deliveries = self.recipients().map(|(i, s)| {
+                let result = s.deliver_with(self.poison_policy, |o| o.notify_with_reply(message, &reply));
+                (i, result.map_or_else(|r| r, |()| DeliveryResult::Delivered))
+            });
+            self.broadcast(message, deliveries)
+        };
+        let mut replies = Vec::new();
+        for _ in 0..notified {
+            let reply = match deadline {
+                Some(deadline) => receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())).ok(),
+                None => receiver.recv().ok(),
+            };
+            let Some(reply) = reply else {
+                break;
+            };
+            replies.extend(reply.downcast().ok().map(|r: Box<R>| *r));
+        }
+        replies
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but a panic in one observer's `notify` is
+    /// caught instead of unwinding through the send, so later subscribers are still
+    /// notified. Returns the number of observers notified and the indices of those that
+    /// panicked.
+    ///
+    /// Observers are not required to be `UnwindSafe`: each call is wrapped in
+    /// [`AssertUnwindSafe`], so an observer that panicked may be left half-updated. The
+    /// panic is caught while its lock is still held, which leaves the mutex unpoisoned.
+    pub fn send_to_all_isolated(&self, message: &T) -> (usize, Vec<usize>) {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return (0, Vec::new());
+        };
+        self.log(message);
+        let mut panicked = Vec::new();
+        let results: Vec<_> = self.recipients()
+            .map(|(i, s)| match s.deliver_with(self.poison_policy, |o| panic::catch_unwind(AssertUnwindSafe(|| o.notify_action(message)))) {
+                Ok(Ok(action)) => {
+                    s.apply(action);
+                    DeliveryResult::Delivered
+                }
+                Ok(Err(_)) => {
+                    panicked.push(i);
+                    DeliveryResult::Poisoned
+                }
+                Err(r) => r,
+            })
+            .collect();
+        (self.record(1, results), panicked)
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but an observer registered several times
+    /// is notified only once, at its first registration. Returns the number of distinct
+    /// observers notified.
+    ///
+    /// Observers are told apart by the address of their allocation, which costs a hash
+    /// set insertion per live subscriber on top of the plain send.
+    pub fn send_to_all_unique(&self, message: &T) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let mut seen = HashSet::new();
+        let results: Vec<_> = self.recipients()
+            .filter_map(|(_, s)| match s.upgrade() {
+                None => Some(s.stale()),
+                Some(o) => seen.insert(Arc::as_ptr(&o) as *const () as usize).then(|| match lock(&o, self.poison_policy, &s.cancelled) {
+                    Ok(mut o) => DeliveryResult::notified(s.notify(&mut *o, message)),
+                    Err(r) => r,
+                }),
+            })
+            .collect();
+        self.record(1, results)
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but never waits for an observer's lock:
+    /// observers currently locked elsewhere are skipped. Returns the number notified and
+    /// the indices of the skipped ones.
+    ///
+    /// Useful when the thread holding an observer's lock may itself be waiting on the
+    /// caller, where a blocking send would deadlock.
+    pub fn send_to_all_try(&self, message: &T) -> (usize, Vec<usize>) {
+        self.send_to_all_timeout(message, Duration::ZERO)
+    }
+    /// Like [`send_to_all_try`](Self::send_to_all_try), but keeps retrying a locked
+    /// observer for up to `per_lock` before skipping it.
+    ///
+    /// `Mutex` has no timed lock, so the wait is a `try_lock` loop that yields the thread
+    /// between attempts; it keeps a core busy while an observer is stuck. A `per_lock`
+    /// too long to be represented as a deadline waits for each lock like
+    /// [`send_to_all`](Self::send_to_all).
+    pub fn send_to_all_timeout(&self, message: &T, per_lock: Duration) -> (usize, Vec<usize>) {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return (0, Vec::new());
+        };
+        self.log(message);
+        let mut skipped = Vec::new();
+        let results: Vec<_> = self.recipients()
+            .filter_map(|(i, s)| {
+                let Some(deadline) = Instant::now().checked_add(per_lock) else {
+                    return Some(s.deliver(message, self.poison_policy));
+                };
+                loop {
+                    if let Some(result) = self.try_deliver(s, message) {
+                        return Some(result);
+                    }
+                    if Instant::now() >= deadline {
+                        skipped.push(i);
+                        return None;
+                    }
+                    std::thread::yield_now();
+                }
+            })
+            .collect();
+        (self.record(1, results), skipped)
+    }
+    /// Like [`send_to_all_try`](Self::send_to_all_try), but goes back to the skipped
+    /// observers up to `max_attempts` more times, sleeping for `backoff` before each
+    /// round. Returns the number notified and the indices still locked after the last
+    /// round.
+    pub fn send_to_all_retry(&self, message: &T, max_attempts: usize, backoff: Duration) -> (usize, Vec<usize>) {
+        self.send_to_all_retry_with(message, max_attempts, || std::thread::sleep(backoff))
+    }
+    // `send_to_all_retry` with the wait between rounds left to `wait`.
+    fn send_to_all_retry_with(&self, message: &T, max_attempts: usize, mut wait: impl FnMut()) -> (usize, Vec<usize>) {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return (0, Vec::new());
+        };
+        self.log(message);
+        let mut results = Vec::new();
+        let mut pending: Vec<_> = self.recipients().map(|(i, _)| i).collect();
+        for attempt in 0..=max_attempts {
+            if attempt > 0 {
+                if pending.is_empty() {
+                    break;
+                }
+                wait();
+            }
+            pending.retain(|&i| match self.entry(i).and_then(|s| self.try_deliver(s, message)) {
+                Some(result) => {
+                    results.push(result);
+                    false
+                }
+                None => true,
+            });
+        }
+        (self.record(1, results), pending)
+    }
+    /// Tries to notify every live subscriber until `deadline`, for loops with a fixed
+    /// budget per frame. An observer locked elsewhere is retried, as with
+    /// [`send_to_all_timeout`](Self::send_to_all_timeout), up to the deadline rather than
+    /// for a fixed time each. Returns the number notified and the indices of the
+    /// subscribers not reached in time, whether they were locked or never tried because
+    /// the deadline had passed. Nested sends are ignored as with
+    /// [`send_to_all`](Self::send_to_all).
+    ///
+    /// A slow `notify` is not interrupted: the deadline is only checked between attempts.
+    pub fn send_to_all_deadline(&self, message: &T, deadline: Instant) -> (usize, Vec<usize>) {
+        self.send_to_all_deadline_with(message, deadline, Instant::now)
+    }
+    // `send_to_all_deadline` reading the time from `now`.
+    fn send_to_all_deadline_with(&self, message: &T, deadline: Instant, mut now: impl FnMut() -> Instant) -> (usize, Vec<usize>) {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return (0, Vec::new());
+        };
+        self.log(message);
+        let mut unreached = Vec::new();
+        let results: Vec<_> = self.recipients()
+            .filter_map(|(i, s)| loop {
+                if now() >= deadline {
+                    unreached.push(i);
+                    return None;
+                }
+                if let Some(result) = self.try_deliver(s, message) {
+                    return Some(result);
+                }
+                std::thread::yield_now();
+            })
+            .collect();
+        (self.record(1, results), unreached)
+    }
+    /// Sends `message` to every live subscriber, returning how many were notified. A
+    /// [`Low`](EventPriority::Low) priority message skips observers that are locked
+    /// elsewhere, like [`send_to_all_try`](Self::send_to_all_try), so that it is dropped
+    /// rather than adding to the wait under load; a [`High`](EventPriority::High) one waits
+    /// for them. Nested sends are ignored as with [`send_to_all`](Self::send_to_all).
+    pub fn send_prioritized(&self, message: &T, priority: EventPriority) -> usize {
+        if priority == EventPriority::High {
+            return self.send_to_all(message);
+        }
+        self.send_to_all_try(message).0
+    }
+    // One attempt at notifying `s` that never blocks: `None` if its observer is locked
+    // elsewhere.
+    fn try_deliver(&self, s: &Entry<T>, message: &T) -> Option<DeliveryResult> {
+        let Some(o) = s.upgrade() else {
+            return Some(s.stale());
+        };
+        let locked = match o.try_lock() {
+            Ok(o) => Ok(o),
+            Err(TryLockError::Poisoned(poisoned)) => recover(&o, poisoned, self.poison_policy, &s.cancelled),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+        Some(match locked {
+            Ok(mut o) => DeliveryResult::notified(s.notify(&mut *o, message)),
+            Err(r) => r,
+        })
+    }
+    /// Delivers every message in `messages` to each subscriber in turn, locking each
+    /// observer once for the whole slice, and returns the number of (observer, message)
+    /// deliveries.
+    ///
+    /// Delivery is observer-major: the first subscriber sees all messages before the
+    /// second sees any. Call [`send_to_all`](Self::send_to_all) in a loop for
+    /// message-major order. A one-shot subscriber only receives the first message.
+    /// Nested sends are ignored as with `send_to_all`.
+    pub fn send_batch(&self, messages: &[T]) -> usize
+    where
+        T: Sized,
+    {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        let results = self.recipients()
+            .flat_map(|(_, s)| {
+                let (result, count) = match s.upgrade() {
+                    None => (s.stale(), messages.len()),
+                    Some(o) => match lock(&o, self.poison_policy, &s.cancelled) {
+                        Ok(mut o) => (DeliveryResult::Delivered, messages.iter().take_while(|m| s.notify(&mut *o, m)).count()),
+                        Err(r) => (r, messages.len()),
+                    },
+                };
+                std::iter::repeat_n(result, count)
+            });
+        self.record(messages.len(), results)
+    }
+    /// Notifies every live subscriber on its own scoped thread and waits for all of them,
+    /// returning how many were notified.
+    ///
+    /// Each observer sits behind its own mutex, so the threads never contend with each
+    /// other; a slow observer only delays the return of this call, not its siblings.
+    ///
+    /// `notify` runs on another thread, out of reach of the nested-send check: a send to
+    /// this observable from inside `notify` deadlocks on the observer's own lock.
+    pub fn send_to_all_parallel(&self, message: &T) -> usize
+    where
+        T: Sync,
+    {
+        self.log(message);
+        let mut dead = Vec::new();
+        let live: Vec<_> = self.recipients()
+            .filter_map(|(_, s)| match s.upgrade() {
+                Some(o) => Some((s, o)),
+                None => {
+                    dead.push(s.stale());
+                    None
+                }
+            })
+            .collect();
+        let policy = self.poison_policy;
+        let results: Vec<_> = std::thread::scope(|scope| {
+            live.iter()
+                .map(|(s, o)| scope.spawn(move || match lock(o, policy, &s.cancelled) {
+                    Ok(mut o) => DeliveryResult::notified(s.notify(&mut *o, message)),
+                    Err(r) => r,
+                }))
+                .collect::<Vec<_>>()
+                .into_iter()
+                // a panicking observer poisons its mutex on the way out
+                .map(|handle| handle.join().unwrap_or(DeliveryResult::Poisoned))
+                .collect()
+        });
+        self.record(1, results.into_iter().chain(dead))
+    }
+    /// Like [`send_to_all_parallel`](Self::send_to_all_parallel), but returns as soon as
+    /// the notifications are spawned; [`DeliveryJoin::wait`] blocks until they finish.
+    ///
+    /// The spawned threads outlive this call, so the message is shared through an `Arc`.
+    /// As with `send_to_all_parallel`, a send made from inside `notify` is not detected
+    /// and deadlocks.
+    pub fn send_to_all_detached(&self, message: Arc<T>) -> DeliveryJoin
+    where
+        T: Send + Sync + 'static,
+    {
+        self.log(&message);
+        let mut dead = Vec::new();
+        let live: Vec<_> = self.recipients()
+            .filter_map(|(_, s)| match s.upgrade() {
+                Some(o) => Some((s.options.once, s.cancelled.clone(), o)),
+                None => {
+                    dead.push(s.stale());
+                    None
+                }
+            })
+            .collect();
+        self.record(1, dead);
+        let policy = self.poison_policy;
+        let handles = live.into_iter()
+            .map(|(once, cancelled, o)| {
+                let message = message.clone();
+                std::thread::spawn(move || match lock(&o, policy, &cancelled) {
+                    Ok(mut o) if claim(once, &cancelled) => {
+                        if o.notify_action(&message) == NotifyAction::Unsubscribe {
+                            cancelled.store(true, Ordering::Release);
+                        }
+                        DeliveryResult::Delivered
+                    }
+                    Ok(_) => DeliveryResult::Dropped,
+                    Err(r) => r,
+                })
+            })
+            .collect();
+        DeliveryJoin { handles, stats: self.stats.clone() }
+    }
+    /// Notifies subscribers in order through [`Observer::notify_stoppable`] until one
+    /// returns [`Propagation::Stop`], returning how many were notified (including that one).
+    ///
+    /// Delivery follows subscriber order, so a higher priority observer can stop an
+    /// event before it reaches lower priority ones, never the other way round. Nested
+    /// sends are ignored as with `send_to_all`.
+    pub fn send_to_all_stoppable(&self, message: &T) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let mut results = Vec::new();
+        for (_, s) in self.recipients() {
+            match s.deliver_with(self.poison_policy, |o| o.notify_stoppable(message)) {
+                Ok(propagation) => {
+                    results.push(DeliveryResult::Delivered);
+                    if propagation == Propagation::Stop {
+                        break;
+                    }
+                },
+                Err(r) => results.push(r),
+            }
+        }
+        self.record(1, results)
+    }
+    /// Like [`send_to_all`](Self::send_to_all), but checks `cancel` before each delivery
+    /// and stops as soon as it is set, returning how many were notified until then.
+    ///
+    /// The flag can be raised from another thread or by an observer; a delivery already
+    /// under way is not interrupted. Nested sends are ignored as with `send_to_all`.
+    pub fn send_to_all_cancellable(&self, message: &T, cancel: &AtomicBool) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.log(message);
+        let results = self.recipients()
+            .take_while(|_| !cancel.load(Ordering::Acquire))
+            .map(|(_, s)| s.deliver(message, self.poison_policy));
+        self.record(1, results)
+    }
+    /// Calls `f` with the index of every live subscriber and the locked observer, without
+    /// sending anything. Dead and poisoned subscribers are skipped.
+    ///
+    /// Each lock is released before the next subscriber is visited, so `f` never sees
+    /// two observers locked at once.
+    pub fn for_each_live<F>(&self, f: F)
+    where
+        F: FnMut(usize, &mut dyn Observer<T>),
+    {
+        self.visit_all_mut(f);
+    }
+    /// Like [`for_each_live`](Self::for_each_live), but returns the indices of the dead
+    /// and poisoned subscribers it skipped, for diagnostics.
+    pub fn visit_all_mut<F>(&self, mut f: F) -> Vec<usize>
+    where
+        F: FnMut(usize, &mut dyn Observer<T>),
+    {
+        self.entries()
+            .filter(|&(i, s)| {
+                let Some(o) = s.upgrade() else {
+                    return true;
+                };
+                let visited = lock(&o, self.poison_policy, &s.cancelled).map(|mut o| f(i, &mut *o)).is_ok();
+                !visited
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+    /// Locks each live subscriber in delivery order and returns the index of the first for
+    /// which `f` returns `true`, without locking the rest. Dead and poisoned subscribers are
+    /// skipped. To look at the concrete observer, `f` can downcast through
+    /// [`Observer::as_any_mut`].
+    pub fn find_live<F>(&self, mut f: F) -> Option<usize>
+    where
+        F: FnMut(&mut dyn Observer<T>) -> bool,
+    {
+        self.entries()
+            .find(|(_, s)| {
+                s.upgrade().is_some_and(|o| lock(&o, self.poison_policy, &s.cancelled).is_ok_and(|mut o| f(&mut *o)))
+            })
+            .map(|(i, _)| i)
+    }
+    /// Calls [`Observer::flush`] on every live subscriber, returning how many were flushed.
+    /// Dead and poisoned subscribers are skipped, and so is a call made from inside one of
+    /// this observable's own `notify` calls, which returns 0. Disabled groups are flushed
+    /// too: the signal is not an event.
+    pub fn flush_all(&self) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        self.entries()
+            .filter(|(_, s)| {
+                s.upgrade().is_some_and(|o| lock(&o, self.poison_policy, &s.cancelled).map(|mut o| o.flush()).is_ok())
+            })
+            .count()
+    }
+    /// Runs `f` on the locked observer at index `i` without sending anything, returning its
+    /// result, or `None` if there is no live, unpoisoned subscriber at that index.
+    pub fn with_subscriber<R, F>(&self, i: usize, f: F) -> Option<R>
+    where
+        F: FnOnce(&mut dyn Observer<T>) -> R,
+    {
+        let entry = self.entry(i)?;
+        let observer = entry.upgrade()?;
+        let mut observer = lock(&observer, self.poison_policy, &entry.cancelled).ok()?;
+        Some(f(&mut *observer))
+    }
+    /// Sends `message` to the subscribers registered on `topic`, returning how many were notified.
+    /// Nested sends are ignored as with [`send_to_all`](Self::send_to_all).
+    pub fn send_to_topic(&self, topic: &str, message: &T) -> usize {
+        self.send_keyed(message, |key| key.downcast_ref::<String>().is_some_and(|key| key == topic))
+    }
+    // Sends to the subscribers whose topic satisfies `matches`.
+    fn send_keyed(&self, message: &T, matches: impl Fn(&(dyn Any + Send + Sync)) -> bool) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        let results = self.recipients()
+            .filter(|(_, s)| s.options.topic.as_deref().is_some_and(&matches))
+            .map(|(_, s)| s.deliver(message, self.poison_policy));
+        self.record(1, results)
+    }
+    /// Sends `message` to the subscribers whose index satisfies `predicate`,
+    /// returning how many were notified. Nested sends are ignored as with `send_to_all`.
+    pub fn send_filtered<P>(&self, message: &T, predicate: P) -> usize
+    where
+        P: Fn(usize) -> bool,
+    {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        let results = self.recipients()
+            .filter(|&(i, _)| predicate(i))
+            .map(|(_, s)| s.deliver(message, self.poison_policy));
+        self.record(1, results)
+    }
+    /// Sends `message` to the subscribers whose index lies in `range`, in delivery order,
+    /// returning how many were notified. Empty slots and parts of the range past the last
+    /// slot are skipped, and nested sends are ignored as with [`send_to_all`](Self::send_to_all).
+    pub fn send_to_range(&self, message: &T, range: impl RangeBounds<usize>) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        let results = self.recipients()
+            .filter(|(i, _)| range.contains(i))
+            .map(|(_, s)| s.deliver(message, self.poison_policy));
+        self.record(1, results)
+    }
+    /// Sends `message` to the subscribers at `indices`, in the order listed, returning how
+    /// many were notified. An index listed more than once is only sent to the first time.
+    /// As with [`send_to_range`](Self::send_to_range), empty slots, indices past the last
+    /// slot and members of disabled groups are skipped, and nested sends are ignored.
+    pub fn send_to_indices(&self, message: &T, indices: &[usize]) -> usize {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        let mut seen = HashSet::new();
+        let results = indices.iter()
+            .filter(|&&i| seen.insert(i))
+            .filter_map(|&i| self.entry(i))
+            .filter(|s| self.is_group_enabled(s.options.group))
+            .map(|s| s.deliver(message, self.poison_policy));
+        self.record(1, results)
+    }
+    /// Sends `message` to the observers that satisfy `predicate`, returning how many were notified.
+    ///
+    /// The predicate runs while the observer's mutex is held, and the same guard is then
+    /// used for `notify`, so each observer is locked once. It must not try to lock that
+    /// observer again or it will deadlock. Nested sends are ignored as with `send_to_all`.
+    pub fn send_filtered_by<P>(&self, message: &T, predicate: P) -> usize
+    where
+        P: Fn(&dyn Observer<T>) -> bool,
+    {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        let results = self.recipients()
+            .filter_map(|(_, s)| match s.upgrade() {
+                None => Some(s.stale()),
+                Some(o) => match lock(&o, self.poison_policy, &s.cancelled) {
+                    Ok(mut o) => predicate(&*o).then(|| DeliveryResult::notified(s.notify(&mut *o, message))),
+                    Err(r) => Some(r),
+                },
+            });
+        self.record(1, results)
+    }
+    /// Sends `message` to the subscriber in slot `i`.
+    ///
+    /// A slot emptied by [`prune`](Self::prune), [`retain`](Self::retain) or an unregister
+    /// is reused by the next registration, so an index kept across removals may point at a
+    /// different observer; keep the [`Subscription`] and use
+    /// [`send_to_handle`](Self::send_to_handle) instead.
+    pub fn send_to(&self, message: &T, i: usize) -> Option<()> {
+        self.try_send_to(message, i).ok()
+    }
+    /// Sends `message` to one live subscriber drawn at random, each with a chance
+    /// proportional to its weight (see [`register_weighted`](Self::register_weighted))
+    /// among the live, enabled ones, and returns its index. `None` if there is nobody to
+    /// pick, or the one picked turned out poisoned or already used up.
+    pub fn send_to_weighted_random(&self, message: &T) -> Option<usize> {
+        let weight = |s: &Entry<T>| s.options.weight.unwrap_or(1.0).max(0.0);
+        let candidates: Vec<_> = self.recipients()
+            .filter(|(_, s)| weight(s) > 0.0 && s.upgrade().is_some())
+            .collect();
+        let total: f64 = candidates.iter().map(|(_, s)| weight(s)).sum();
+        let mut roll = self.rng.next_f64() * total;
+        // falls back on the last candidate, should rounding leave the roll past them all
+        let &(i, s) = candidates.iter()
+            .find(|(_, s)| {
+                roll -= weight(s);
+                roll < 0.0
+            })
+            .or(candidates.last())?;
+        let result = s.deliver(message, self.poison_policy);
+        self.record(1, Some(result));
+        (result == DeliveryResult::Delivered).then_some(i)
+    }
+    /// Sends `message` to the observer registered by `subscription`, wherever it now sits.
+    ///
+    /// A handle whose registration has been removed fails with [`SendError::Dropped`], or
+    /// with [`SendError::StaleHandle`] once its slot has gone to another registration; the
+    /// observer now in the slot is not notified.
+    pub fn send_to_handle(&self, message: &T, subscription: &Subscription) -> Result<(), SendError> {
+        if self.is_stale(subscription) {
+            self.record(1, Some(DeliveryResult::Dropped));
+            return Err(SendError::StaleHandle);
+        }
+        let result = self.position(subscription)
+            .and_then(|i| self.entry(i))
+            .map_or(DeliveryResult::Dropped, |s| s.deliver(message, self.poison_policy));
+        self.record(1, Some(result));
+        match result {
+            DeliveryResult::Delivered => Ok(()),
+            DeliveryResult::Dropped => Err(SendError::Dropped),
+            DeliveryResult::Poisoned => Err(SendError::Poisoned),
+        }
+    }
+    /// Like [`send_to`](Self::send_to), but reports why delivery failed.
+    pub fn send_to_checked(&self, message: &T, i: usize) -> Result<(), SendError> {
+        self.try_send_to(message, i).map_err(SendError::from)
+    }
+    /// Like [`send_to_checked`](Self::send_to_checked), but an out-of-range index is
+    /// reported together with the offending index.
+    pub fn try_send_to(&self, message: &T, i: usize) -> Result<(), SendToError> {
+        let result = self.entry(i).map(|s| s.deliver(message, self.poison_policy));
+        self.record(1, result);
+        match result {
+            Some(DeliveryResult::Delivered) => Ok(()),
+            Some(DeliveryResult::Dropped) => Err(SendToError::Dropped),
+            Some(DeliveryResult::Poisoned) => Err(SendToError::Poisoned),
+            None => Err(SendToError::Index(i)),
+        }
+    }
+    /// Moves `message` into the subscriber at index `i` through [`Observer::notify_owned`],
+    /// so payloads with a single consumer need not be `Clone`.
+    ///
+    /// The message is handed back if it could not be delivered.
+    pub fn send_owned_to(&self, message: T, i: usize) -> Result<(), T>
+    where
+        T: Sized,
+    {
+        let mut slot = Some(message);
+        let result = self.entry(i)
+            .map(|s| match s.deliver_with(self.poison_policy, |o| slot.take().map(|m| o.notify_owned(m))) {
+                Ok(_) => DeliveryResult::Delivered,
+                Err(r) => r,
+            });
+        self.record(1, result);
+        slot.map_or(Ok(()), Err)
+    }
+    // the cancellation flag is unique to one registration, so it also rejects
+    // handles issued by a different observable that happen to share slot and epoch
+    pub(crate) fn position(&self, subscription: &Subscription) -> Option<usize> {
+        self.entry(subscription.slot)
+            .filter(|s| !self.is_stale(subscription) && Arc::ptr_eq(&s.cancelled, &subscription.cancelled))
+            .map(|_| subscription.slot)
+    }
+    // Whether the handle's slot has been handed to a newer registration since.
+    fn is_stale(&self, subscription: &Subscription) -> bool {
+        self.epochs.get(subscription.slot) != Some(&subscription.epoch)
+    }
+}
+
+// Subscribers are trait objects, so only their counts are shown; no `T: Debug` needed.
+#[cfg(feature = "std")]
+impl<T: ?Sized> fmt::Debug for Observable<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let live = self.live_count();
+        f.debug_struct("Observable")
+            .field("len", &self.len())
+            .field("live", &live)
+            .field("dead", &(self.len() - live))
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> Extend<Weak<Mutex<dyn Observer<T>>>> for Observable<T> {
+    fn extend<I: IntoIterator<Item = Weak<Mutex<dyn Observer<T>>>>>(&mut self, observers: I) {
+        self.register_all(observers);
+    }
+}
+
+/// Copies the subscriber list: the clone notifies the same observers, each registration
+/// keeping its priority, position, topic, name, group and one-shot flag, but from then on
+/// the two lists are independent. Registering, unregistering or pruning on one leaves the
+/// other as it was, and handles returned by the original do not refer to the clone's
+/// registrations. Observers the original owns (see
+/// [`register_owned`](Self::register_owned)) are shared by both until each lets go. Use
+/// [`shared_handle`](Self::shared_handle) when every owner should see the same list.
+///
+/// The clone keeps the capacity, poison policy, pruning, deduplication and disabled-group
+/// settings and the dead-letter handler, and starts with no logger, middleware, delivery
+/// order, queued or paused messages, or statistics.
+#[cfg(feature = "std")]
+impl<T: ?Sized> Clone for Observable<T> {
+    fn clone(&self) -> Self {
+        let mut copy = Observable {
+            slots: self.slots.clone(),
+            free: self.free.clone(),
+            epochs: self.epochs.clone(),
+            order: self.order.clone(),
+            next_id: self.next_id,
+            capacity: self.capacity,
+            auto_prune: self.auto_prune,
+            poison_policy: self.poison_policy,
+            dedup_window: self.dedup_window,
+            disabled_groups: self.disabled_groups.clone(),
+            closed: self.closed,
+            prune_every: self.prune_every,
+            dead_letter: self.dead_letter.clone(),
+            ..Observable::new()
+        };
+        // the copies count their sends apart
+        for entry in copy.slots.iter_mut().flatten() {
+            entry.stats = copy.stats.clone();
+        }
+        copy
+    }
+}
+
+// Written by hand because `#[derive(Default)]` would require `T: Default`.
+#[cfg(feature = "std")]
+impl<T: ?Sized> Default for Observable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Receives messages from an [`Observable`].
+///
+/// Observers are shared through `Arc<Mutex<_>>` and may be notified from other threads
+/// (see [`Observable::send_to_all_parallel`]), so they must be `Send`:
+///
+/// ```compile_fail
+/// use std::rc::Rc;
+/// use rust_exercise_2_event::Observer;
+///
+/// struct NotSend(Rc<u8>);
+///
+/// impl Observer<u8> for NotSend {
+///     fn notify(&mut self, _event: &u8) {}
+/// }
+/// ```
+///
+/// Observables store subscribers as `dyn Observer<T>`, so the trait must stay object
+/// safe: a method added here may not take type parameters or return `Self` unless it is
+/// bounded by `where Self: Sized`. The crate's tests fail to compile if that is broken.
+pub trait Observer<T: ?Sized>: Send {
+    fn notify(&mut self, event: &T);
+    /// Called once per registration just before its first delivery, whichever hook that
+    /// goes through, with the event about to be delivered: for setup that is best done
+    /// lazily. Defaults to doing nothing.
+    ///
+    /// The observable keeps track, so each registration of the same observer, and the
+    /// observer put in by [`Observable::replace`], gets its own first event. Deliveries
+    /// by [`Observable::send_to_all_detached`] are not tracked and do not call it.
+    fn on_first(&mut self, _event: &T) {}
+    /// Variant used by the plain sends ([`Observable::send_to_all`], `send_to`, batches,
+    /// parallel and filtered sends); returning [`NotifyAction::Unsubscribe`] lets an
+    /// observer remove itself. The sends with their own hook (`notify_stoppable`,
+    /// `notify_ctx`, `notify_owned`, `notify_ack`, `notify_indexed`, `handle`, `notify_with_reply`, `query`) and the owned half of
+    /// [`Observable::send_to_all_cloned`] do not consult it.
+    fn notify_action(&mut self, event: &T) -> NotifyAction {
+        self.notify(event);
+        NotifyAction::Keep
+    }
+    /// Variant used by [`Observable::send_to_all_stoppable`]; returning
+    /// [`Propagation::Stop`] keeps the event from later subscribers.
+    fn notify_stoppable(&mut self, event: &T) -> Propagation {
+        self.notify(event);
+        Propagation::Continue
+    }
+    /// Variant used by [`Observable::send_to_all_with_ctx`], receiving metadata about the
+    /// send along with the event.
+    #[cfg(feature = "std")]
+    fn notify_ctx(&mut self, event: &T, _ctx: &EventContext) {
+        self.notify(event)
+    }
+    /// Variant used by [`Observable::send_to_all_indexed`], telling the observer it is the
+    /// `index`th of `total` live subscribers. Defaults to [`notify`](Self::notify).
+    fn notify_indexed(&mut self, event: &T, index: usize, total: usize) {
+        let _ = (index, total);
+        self.notify(event)
+    }
+    /// Variant used by [`Observable::send_to_all_tracked`]; returning `false` reports the
+    /// event as not processed, for the sender to retry. Defaults to
+    /// [`notify`](Self::notify) and acknowledging.
+    fn notify_ack(&mut self, event: &T) -> bool {
+        self.notify(event);
+        true
+    }
+    /// Variant used by [`Observable::send_collect`], letting the observer reject the event
+    /// with a reason. Defaults to [`notify`](Self::notify) and accepting.
+    #[cfg(feature = "std")]
+    fn handle(&mut self, event: &T) -> Result<(), String> {
+        self.notify(event);
+        Ok(())
+    }
+    /// Variant used by [`Observable::send_collect_replies`]: the observer answers through
+    /// `reply`, now or, with a clone of it, later. Defaults to [`notify`](Self::notify)
+    /// and not answering.
+    ///
+    /// [`Reply`] erases the answer's type, for the same reason [`query`](Self::query) does.
+    #[cfg(feature = "std")]
+    fn notify_with_reply(&mut self, event: &T, reply: &Reply) {
+        let _ = reply;
+        self.notify(event)
+    }
+    /// Answers a question broadcast by [`Observable::query_all`]; `None`, the default,
+    /// stays out of the answers.
+    ///
+    /// The answer type is erased rather than an associated type: observables hold
+    /// `dyn Observer<T>` without naming it, and an associated type would have to be
+    /// spelled out in every such object. `query_all` names the type it expects and
+    /// skips answers of any other.
+    #[cfg(feature = "std")]
+    fn query(&mut self, _event: &T) -> Option<Box<dyn Any + Send>> {
+        None
+    }
+    /// By-value variant used by [`Observable::send_owned_to`], letting a single consumer
+    /// take ownership of the event. Defaults to lending it to [`notify`](Self::notify).
+    fn notify_owned(&mut self, event: T)
+    where
+        T: Sized,
+    {
+        self.notify(&event)
+    }
+    /// Asked by [`Observable::send_to_all_cloned`] before each delivery: observers that
+    /// return `true` get their own clone through [`notify_owned`](Self::notify_owned),
+    /// the rest only borrow the message. Defaults to `false`.
+    fn wants_owned(&self) -> bool {
+        false
+    }
+    /// Called by [`Observable::flush_all`], outside of any event, for observers that batch
+    /// what they receive and should hand it on now. Defaults to doing nothing.
+    fn flush(&mut self) {}
+    /// Read hook letting code handed a `&dyn Observer<T>` (for example the predicate of
+    /// [`Observable::send_filtered_by`]) downcast to the concrete type.
+    /// Observers opt in by returning `Some(self)`.
+    fn as_any(&self) -> Option<&dyn Any> {
+        None
+    }
+    /// Mutable counterpart of [`as_any`](Self::as_any).
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        None
+    }
+}
+
+/// Coerces a concrete observer into the `Arc<Mutex<dyn Observer<T>>>` that
+/// [`Observable::register_owned`] stores, for callers that cannot name the type.
+#[cfg(feature = "std")]
+pub fn into_dyn<T, O: Observer<T> + 'static>(observer: Arc<Mutex<O>>) -> Arc<Mutex<dyn Observer<T>>> {
+    observer
+}
+
+/// Adapts any `FnMut(&T)` closure into an [`Observer`].
+pub struct FnObserver<F> {
+    f: F,
+}
+
+impl<F> FnObserver<F> {
+    pub fn new(f: F) -> FnObserver<F> {
+        FnObserver { f }
+    }
+}
+
+impl<T: ?Sized, F: FnMut(&T) + Send> Observer<T> for FnObserver<F> {
+    fn notify(&mut self, event: &T) {
+        (self.f)(event)
+    }
+}
+
+/// Makes a plain function an [`Observer`], under a type that can be named without the
+/// function's own: `FnPtrObserver<T>` for every `fn(&T)`.
+pub struct FnPtrObserver<T: ?Sized>(pub fn(&T));
+
+// by hand, since derives would require `T: Clone`
+impl<T: ?Sized> Clone for FnPtrObserver<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for FnPtrObserver<T> {}
+
+impl<T: ?Sized> From<fn(&T)> for FnPtrObserver<T> {
+    fn from(f: fn(&T)) -> FnPtrObserver<T> {
+        FnPtrObserver(f)
+    }
+}
+
+impl<T: ?Sized> Observer<T> for FnPtrObserver<T> {
+    fn notify(&mut self, event: &T) {
+        (self.0)(event)
+    }
+}
+
+/// Makes a boxed closure an [`Observer`], for handlers chosen at runtime that have no
+/// common type. The closure must be `Send`, as all observers are.
+#[cfg(feature = "std")]
+pub struct BoxFnObserver<T: ?Sized>(pub Box<dyn FnMut(&T) + Send>);
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> BoxFnObserver<T> {
+    pub fn new(f: impl FnMut(&T) + Send + 'static) -> BoxFnObserver<T> {
+        BoxFnObserver(Box::new(f))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: ?Sized> Observer<T> for BoxFnObserver<T> {
+    fn notify(&mut self, event: &T) {
+        (self.0)(event)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+#[allow(clippy::useless_format)] // the baseline `test_observable` formats string literals
+mod tests {
+
+    use crate::{*};
+    use std::sync::Arc;
+
+    #[derive(PartialEq, Debug)]
+    enum MyMessage {
+        Msg(&'static str),
+    }
+
+    // fails to compile if `Observer` stops being object safe
+    const _: fn() = || {
+        let _: &dyn Observer<MyMessage>;
+        let _: &dyn Observer<str>;
+    };
+
+    #[derive(Default)]
+    struct BeforeObserver {
+        output: String,
+        counter: usize,
+    }
+
+    impl Observer<MyMessage> for BeforeObserver {
+        fn notify(&mut self, event: &MyMessage) {
+            self.counter += 1;
+            self.output = match event {
+                MyMessage::Msg(str) => format!("{}, World", str),
+            };
+        }
+        fn as_any(&self) -> Option<&dyn Any> {
+            Some(self)
+        }
+        fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+            Some(self)
+        }
+    }
+
+    #[derive(Default)]
+    struct AfterObserver {
+        output: String,
+        counter: usize,
+    }
+
+    impl Observer<MyMessage> for AfterObserver {
+        fn notify(&mut self, event: &MyMessage) {
+            self.counter += 1;
+            self.output = match event {
+                MyMessage::Msg(str) => format!("Hello, {}", str),
+            }
+        }
+    }
+
+    #[test]
+    fn test_observable() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+
+        // cannot directly cast type (requires unstable rust)
+        // see https://github.com/rust-lang/rfcs/blob/master/text/0982-dst-coercion.md
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.send_to_all(&MyMessage::Msg("1"));
+        
+        {
+            let lock1 = ob1.lock();
+            let ob1 = lock1.as_ref().ok();
+            assert_eq!(ob1.map(|v| v.counter), Some(1usize));
+            assert_eq!(ob1.map(|v| &v.output[..]), Some(&format!("1, World")[..]));
+        }
+
+        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob2d));
+        observable.send_to_all(&MyMessage::Msg("2"));
+
+        {
+            let lock1 = ob1.lock();
+            let ob1 = lock1.as_ref().ok();
+            let lock2 = ob2.lock();
+            let ob2 = lock2.as_ref().ok();
+            assert_eq!(ob1.map(|v| v.counter), Some(2usize));
+            assert_eq!(ob1.map(|v| &v.output[..]), Some(&format!("2, World")[..]));
+            assert_eq!(ob2.map(|v| v.counter), Some(1usize));
+            assert_eq!(ob2.map(|v| &v.output[..]), Some(&format!("Hello, 2")[..]));
+        }
+
+        observable.send_to(&MyMessage::Msg("3"), 1);
+
+        {
+            let lock1 = ob1.lock();
+            let ob1 = lock1.as_ref().ok();
+            let lock2 = ob2.lock();
+            let ob2 = lock2.as_ref().ok();
+            assert_eq!(ob1.map(|v| v.counter), Some(2usize));
+            assert_eq!(ob1.map(|v| &v.output[..]), Some(&format!("2, World")[..]));
+            assert_eq!(ob2.map(|v| v.counter), Some(2usize));
+            assert_eq!(ob2.map(|v| &v.output[..]), Some(&format!("Hello, 3")[..]));
+        }
+    }
+
+    #[test]
+    fn test_unregister_observer() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob1w = Arc::downgrade(&ob1d);
+        observable.register(ob1w.clone());
+        observable.register(ob1w.clone());
+
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 2);
+
+        assert!(observable.unregister_observer(&ob1w));
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 1);
+
+        assert!(observable.unregister_observer(&ob1w));
+        assert!(!observable.unregister_observer(&ob1w));
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("3")), 0);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(3usize));
+    }
+
+    #[test]
+    fn test_subscription() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        let sub1 = observable.register(Arc::downgrade(&ob1d));
+        let sub2 = observable.register(Arc::downgrade(&ob2d));
+
+        assert!(observable.unregister(sub1));
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 1);
+
+        // sub2 still refers to ob2 even though it moved to index 0
+        assert!(observable.unregister(sub2));
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 0);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(0usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(1usize));
+
+        let mut sub1 = observable.register(Arc::downgrade(&ob1d));
+        let sub2 = observable.register(Arc::downgrade(&ob2d));
+        sub1.set_drop_unsubscribes(true);
+        drop(sub1);
+        drop(sub2);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("3")), 1);
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(0usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(2usize));
+    }
+
+    #[test]
+    fn test_churn_does_not_grow() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        let mut sub = observable.register(Arc::downgrade(&ob2));
+        for _ in 0..100 {
+            assert!(observable.unregister(sub));
+            sub = observable.register(Arc::downgrade(&ob2));
+        }
+        assert_eq!(observable.len(), 2);
+        assert_eq!(observable.slots.len(), 2);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 2);
+    }
+
+    #[test]
+    fn test_slot_reuse() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob3: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob3d: Arc<Mutex<dyn Observer<MyMessage>>> = ob3.clone();
+        let ob4: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob4d: Arc<Mutex<dyn Observer<MyMessage>>> = ob4.clone();
+        observable.register(Arc::downgrade(&ob1));
+        let sub2 = observable.register(Arc::downgrade(&ob2));
+        observable.register(Arc::downgrade(&ob3d));
+        drop(ob2);
+        assert_eq!(observable.prune(), 1);
+
+        // the survivors keep their indices
+        assert_eq!(observable.send_to(&MyMessage::Msg("1"), 2), Some(()));
+        assert_eq!(ob3.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(observable.send_to(&MyMessage::Msg("2"), 1), None);
+
+        // the vacated slot goes to the next registration, which the old handle does not reach
+        let sub4 = observable.register_with_priority(Arc::downgrade(&ob4d), 1);
+        assert_eq!(observable.position(&sub4), Some(1));
+        assert_eq!(observable.send_to_handle(&MyMessage::Msg("3"), &sub2), Err(SendError::StaleHandle));
+        assert!(!observable.unregister(sub2));
+        assert_eq!(observable.send_to(&MyMessage::Msg("4"), 1), Some(()));
+        assert_eq!(ob4.lock().ok().map(|v| v.output.clone()), Some("4, World".to_string()));
+
+        // delivery still follows priority rather than slots
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let observers = order_observers(2, &log);
+        observable.clear();
+        observable.register(Arc::downgrade(&observers[0]));
+        observable.register_with_priority(Arc::downgrade(&observers[1]), 1);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("5")), 2);
+        assert_eq!(*log.lock().unwrap(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_stale_handle() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<usize>::new();
+        let (_a, weak_a) = recorder();
+        let (b, weak_b) = recorder();
+        let h1 = observable.register(weak_a);
+        let stale = Subscription { epoch: h1.epoch, slot: h1.slot, cancelled: h1.cancelled.clone(), drop_unsubscribes: false };
+        assert!(observable.unregister(h1));
+        assert_eq!(observable.send_to_handle(&1, &stale), Err(SendError::Dropped));
+
+        let h2 = observable.register(weak_b);
+        assert_eq!(h2.slot, stale.slot);
+        assert_eq!(observable.send_to_handle(&2, &stale), Err(SendError::StaleHandle));
+        assert!(!observable.unregister(stale));
+        assert_eq!(received(&b), Vec::<usize>::new());
+
+        // the epochs survive `clear`, so an old handle cannot match slot 0 again
+        observable.clear();
+        let (_c, weak_c) = recorder();
+        observable.register(weak_c);
+        assert_eq!(observable.send_to_handle(&3, &h2), Err(SendError::StaleHandle));
+    }
+
+    #[test]
+    fn test_replace() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<usize>::new();
+        let (first, weak1) = recorder();
+        let (second, weak2) = recorder();
+        let (_other, weak3) = recorder();
+        let handle = observable.register_with_priority(weak1, 5);
+        observable.register(weak3);
+
+        assert_eq!(observable.replace(&handle, weak2), Ok(()));
+        assert_eq!(observable.send_to_handle(&1, &handle), Ok(()));
+        assert_eq!(observable.send_to_all(&2), 2);
+        assert_eq!(received(&first), Vec::<usize>::new());
+        assert_eq!(received(&second), vec![1, 2]);
+        assert_eq!(observable.position(&handle), Some(0));
+        assert_eq!(observable.entries().next().map(|(i, _)| i), Some(0));
+
+        let (_new, weak4) = recorder();
+        let stale = Subscription { epoch: handle.epoch, slot: handle.slot, cancelled: handle.cancelled.clone(), drop_unsubscribes: false };
+        assert!(observable.unregister(handle));
+        assert_eq!(observable.replace(&stale, weak4.clone()), Err(ReplaceError::Removed));
+        observable.register(weak4.clone());
+        assert_eq!(observable.replace(&stale, weak4), Err(ReplaceError::StaleHandle));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut a = Observable::<MyMessage>::new();
+        let mut b = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let merged = Observable::merge([&mut a, &mut b], Arc::downgrade(&ob1d));
+        assert_eq!(merged.subscriptions().len(), 2);
+
+        assert_eq!(a.send_to_all(&MyMessage::Msg("1")), 1);
+        assert_eq!(b.send_to_all(&MyMessage::Msg("2")), 1);
+
+        drop(merged);
+        assert_eq!(a.send_to_all(&MyMessage::Msg("3")), 0);
+        assert_eq!(b.send_to_all(&MyMessage::Msg("4")), 0);
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(2usize));
+    }
+
+    #[test]
+    fn test_send_to_handle() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob3: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob3d: Arc<Mutex<dyn Observer<MyMessage>>> = ob3.clone();
+        let sub1 = observable.register(Arc::downgrade(&ob1d));
+        let sub2 = observable.register(Arc::downgrade(&ob2));
+        let sub3 = observable.register(Arc::downgrade(&ob3d));
+        drop(ob2);
+        assert_eq!(observable.prune(), 1);
+
+        // ob3 keeps its slot through the prune, and the handle finds it either way
+        assert_eq!(observable.send_to_handle(&MyMessage::Msg("1"), &sub3), Ok(()));
+        assert_eq!(observable.send_to_handle(&MyMessage::Msg("2"), &sub1), Ok(()));
+        assert_eq!(observable.send_to_handle(&MyMessage::Msg("3"), &sub2), Err(SendError::Dropped));
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(ob3.lock().ok().map(|v| v.counter), Some(1usize));
+    }
+
+    #[test]
+    fn test_prune() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let mut observers: Vec<Arc<Mutex<dyn Observer<MyMessage>>>> = Vec::new();
+        for _ in 0..4 {
+            let ob: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+            observable.register(Arc::downgrade(&ob));
+            observers.push(ob);
+        }
+        observers.truncate(2);
+
+        assert_eq!(observable.prune(), 2);
+        assert_eq!(observable.prune(), 0);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 2);
+    }
+
+    #[test]
+    fn test_live_count() {
+        let mut observable = Observable::<MyMessage>::new();
+        assert!(observable.is_empty());
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        observable.register(Arc::downgrade(&ob2));
+        assert_eq!(observable.live_count(), 2);
+        assert_eq!(observable.len(), 2);
+
+        drop(ob1);
+        assert_eq!(observable.live_count(), 1);
+        assert_eq!(observable.len(), 2);
+        assert!(!observable.is_empty());
+
+        drop(ob2);
+        assert!(observable.is_empty());
+        assert_eq!(observable.len(), 2);
+        observable.prune();
+        assert_eq!(observable.len(), 0);
+    }
+
+    fn poison<O: ?Sized>(observer: &Arc<Mutex<O>>) {
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _guard = observer.lock();
+            panic!("poisoning observer");
+        }));
+    }
+
+    #[test]
+    fn test_send_to_all_isolated() {
+        struct PanicObserver;
+
+        impl Observer<MyMessage> for PanicObserver {
+            fn notify(&mut self, _event: &MyMessage) {
+                panic!("observer failed");
+            }
+        }
+
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(PanicObserver));
+        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1));
+        observable.register(Arc::downgrade(&ob2d));
+
+        assert_eq!(observable.send_to_all_isolated(&MyMessage::Msg("1")), (1, vec![0]));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(1usize));
+        assert!(!ob1.is_poisoned());
+    }
+
+    #[test]
+    fn test_send_to_all_try() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let holder = {
+            let ob1 = ob1.clone();
+            std::thread::spawn(move || {
+                let _guard = ob1.lock().unwrap();
+                locked_tx.send(()).unwrap();
+                let _ = done_rx.recv();
+            })
+        };
+        locked_rx.recv().unwrap();
+        assert_eq!(observable.send_to_all_try(&MyMessage::Msg("1")), (1, vec![0]));
+        drop(done_tx);
+        holder.join().unwrap();
+
+        assert_eq!(observable.send_to_all_try(&MyMessage::Msg("2")), (2, vec![]));
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(2usize));
+    }
+
+    #[test]
+    fn test_send_to_all_unique() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+        observable.register(Arc::downgrade(&ob1d));
+
+        assert_eq!(observable.send_to_all_unique(&MyMessage::Msg("1")), 2);
+        assert_eq!(observable.send_to_all_unique(&MyMessage::Msg("2")), 2);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("3")), 3);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(4usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(3usize));
+    }
+
+    #[test]
+    fn test_send_to_all_detailed() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob3: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        observable.register(Arc::downgrade(&ob2));
+        observable.register(Arc::downgrade(&ob3));
+        drop(ob2);
+        poison(&ob3);
+
+        assert_eq!(
+            observable.send_to_all_detailed(&MyMessage::Msg("1")),
+            vec![DeliveryResult::Delivered, DeliveryResult::Dropped, DeliveryResult::Poisoned],
+        );
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 1);
+    }
+
+    #[test]
+    fn test_send_to_all_complete() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob3: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        observable.register(Arc::downgrade(&ob2));
+        observable.register(Arc::downgrade(&ob3));
+        drop(ob3);
+
+        assert!(observable.send_to_all_complete(&MyMessage::Msg("1")));
+        poison(&ob2);
+        assert!(!observable.send_to_all_complete(&MyMessage::Msg("2")));
+    }
+
+    #[test]
+    fn test_send_to_checked() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob3: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        observable.register(Arc::downgrade(&ob2));
+        observable.register(Arc::downgrade(&ob3));
+        drop(ob2);
+        poison(&ob3);
+
+        let msg = MyMessage::Msg("1");
+        assert_eq!(observable.send_to_checked(&msg, 0), Ok(()));
+        assert_eq!(observable.send_to_checked(&msg, 1), Err(SendError::Dropped));
+        assert_eq!(observable.send_to_checked(&msg, 2), Err(SendError::Poisoned));
+        assert_eq!(observable.send_to_checked(&msg, 3), Err(SendError::OutOfBounds));
+        assert_eq!(observable.send_to(&msg, 2), None);
+    }
+
+    #[test]
+    fn test_register_fn() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let sub = observable.register_fn(move |event: &MyMessage| {
+            sink.lock().unwrap().push(match event {
+                MyMessage::Msg(str) => *str,
+            });
+        });
+
+        // the closure is kept alive by the observable even though nothing else holds it
+        assert_eq!(observable.prune(), 0);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 1);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 1);
+        assert_eq!(*received.lock().unwrap(), vec!["1", "2"]);
+        assert_eq!(Arc::strong_count(&received), 2);
+
+        assert!(observable.unregister(sub));
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("3")), 0);
+        assert_eq!(Arc::strong_count(&received), 1);
+    }
+
+    #[test]
+    fn test_register_concrete() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1 = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2 = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register_concrete(&ob1);
+        observable.register_owned(into_dyn(ob2.clone()));
+
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 2);
+        drop(ob1);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 1);
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(2usize));
+    }
+
+    #[test]
+    fn test_register_owned() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob1w = Arc::downgrade(&ob1);
+        observable.register_owned(ob1d);
+        drop(ob1);
+
+        assert_eq!(observable.prune(), 0);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 1);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 1);
+
+        let ob1 = ob1w.upgrade().unwrap();
+        let lock1 = ob1.lock();
+        let ob1 = lock1.as_ref().ok();
+        assert_eq!(ob1.map(|v| v.counter), Some(2usize));
+        assert_eq!(ob1.map(|v| &v.output[..]), Some("2, World"));
+    }
+
+    #[test]
+    fn test_send_filtered() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        let ob3: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob3d: Arc<Mutex<dyn Observer<MyMessage>>> = ob3.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+        observable.register(Arc::downgrade(&ob3d));
+
+        assert_eq!(observable.send_filtered(&MyMessage::Msg("1"), |i| i != 0), 2);
+        assert_eq!(
+            observable.send_filtered_by(&MyMessage::Msg("2"), |o| {
+                o.as_any().is_some_and(|o| o.is::<BeforeObserver>())
+            }),
+            2,
+        );
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(ob3.lock().ok().map(|v| v.counter), Some(2usize));
+    }
+
+    // blocks in `notify` until the test lets it through
+    struct GatedObserver {
+        release: std::sync::mpsc::Receiver<()>,
+        counter: usize,
+    }
+
+    impl Observer<MyMessage> for GatedObserver {
+        fn notify(&mut self, _event: &MyMessage) {
+            let _ = self.release.recv();
+            self.counter += 1;
+        }
+    }
+
+    // waits in `notify` for every other observer sharing the barrier to get there too
+    struct BarrierObserver {
+        barrier: Arc<std::sync::Barrier>,
+        counter: usize,
+    }
+
+    impl Observer<MyMessage> for BarrierObserver {
+        fn notify(&mut self, _event: &MyMessage) {
+            self.barrier.wait();
+            self.counter += 1;
+        }
+    }
+
+    #[test]
+    fn test_send_to_all_parallel() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        // the send can only finish if the two observers are notified at the same time
+        let barrier = Arc::new(std::sync::Barrier::new(2));
+        let ob1 = Arc::new(Mutex::new(BarrierObserver { barrier: barrier.clone(), counter: 0 }));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2 = Arc::new(Mutex::new(BarrierObserver { barrier, counter: 0 }));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+
+        assert_eq!(observable.send_to_all_parallel(&MyMessage::Msg("1")), 2);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(1usize));
+    }
+
+    #[test]
+    fn test_send_to_all_detached() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let (release1, gate1) = std::sync::mpsc::channel();
+        let (release2, gate2) = std::sync::mpsc::channel();
+        let ob1 = Arc::new(Mutex::new(GatedObserver { release: gate1, counter: 0 }));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2 = Arc::new(Mutex::new(GatedObserver { release: gate2, counter: 0 }));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+
+        // returns with both observers still held at their gates
+        let join = observable.send_to_all_detached(Arc::new(MyMessage::Msg("1")));
+        assert_eq!(observable.stats().total_delivered, 0);
+        release1.send(()).unwrap();
+        release2.send(()).unwrap();
+
+        assert_eq!(join.wait(), 2);
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(observable.stats().total_delivered, 2);
+    }
+
+    #[test]
+    fn test_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<MyMessage>();
+        assert_send_sync::<Observable<MyMessage>>();
+        assert_send_sync::<Subscription>();
+    }
+
+    #[test]
+    fn test_register_once() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register_once(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 2);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 1);
+        assert_eq!(observable.send_to_all_parallel(&MyMessage::Msg("3")), 1);
+        // the spent entry lingers until pruned
+        assert_eq!(observable.len(), 2);
+        assert_eq!(observable.prune(), 1);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(ob1.lock().ok().map(|v| v.output.clone()), Some("1, World".to_string()));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(3usize));
+    }
+
+    struct OrderObserver {
+        id: usize,
+        log: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl Observer<MyMessage> for OrderObserver {
+        fn notify(&mut self, _event: &MyMessage) {
+            self.log.lock().unwrap().push(self.id);
+        }
+    }
+
+    fn order_observers(n: usize, log: &Arc<Mutex<Vec<usize>>>) -> Vec<Arc<Mutex<dyn Observer<MyMessage>>>> {
+        (0..n)
+            .map(|id| Arc::new(Mutex::new(OrderObserver { id, log: log.clone() })) as Arc<Mutex<dyn Observer<MyMessage>>>)
+            .collect()
+    }
+
+    #[test]
+    fn test_register_with_priority() {
+        let mut observable = Observable::<MyMessage>::new();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let observers = order_observers(4, &log);
+
+        observable.register_with_priority(Arc::downgrade(&observers[0]), 0);
+        observable.register_with_priority(Arc::downgrade(&observers[1]), 10);
+        observable.register_with_priority(Arc::downgrade(&observers[2]), 5);
+        observable.register_with_priority(Arc::downgrade(&observers[3]), 10);
+
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 4);
+        assert_eq!(*log.lock().unwrap(), vec![1, 3, 2, 0]);
+    }
+
+    #[test]
+    fn test_send_to_all_reverse() {
+        let mut observable = Observable::<MyMessage>::new();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let observers = order_observers(5, &log);
+
+        for observer in &observers[..3] {
+            observable.register(Arc::downgrade(observer));
+        }
+        assert_eq!(observable.send_to_all_reverse(&MyMessage::Msg("1")), 3);
+        assert_eq!(*log.lock().unwrap(), vec![2, 1, 0]);
+
+        log.lock().unwrap().clear();
+        observable.register_with_priority(Arc::downgrade(&observers[3]), 10);
+        observable.register_with_priority(Arc::downgrade(&observers[4]), 10);
+        assert_eq!(observable.send_to_all_reverse(&MyMessage::Msg("2")), 5);
+        assert_eq!(*log.lock().unwrap(), vec![4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_send_to_all_ordered() {
+        let mut observable = Observable::<MyMessage>::new();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let observers = order_observers(4, &log);
+
+        observable.register_with_priority(Arc::downgrade(&observers[0]), 0);
+        let middle = observable.register_with_priority(Arc::downgrade(&observers[1]), 10);
+        observable.register_with_priority(Arc::downgrade(&observers[2]), 5);
+        assert!(observable.unregister(middle));
+        observable.register_with_priority(Arc::downgrade(&observers[3]), 20);
+
+        assert_eq!(observable.send_to_all_ordered(&MyMessage::Msg("1")), 3);
+        assert_eq!(*log.lock().unwrap(), vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_send_to_all_with_ctx() {
+        #[derive(Default)]
+        struct CtxObserver {
+            contexts: Vec<EventContext>,
+        }
+
+        impl Observer<MyMessage> for CtxObserver {
+            fn notify(&mut self, _event: &MyMessage) {}
+            fn notify_ctx(&mut self, _event: &MyMessage, ctx: &EventContext) {
+                self.contexts.push(*ctx);
+            }
+        }
+
+        let mut observable = Observable::<MyMessage>::new();
+        let ob1: Arc<Mutex<CtxObserver>> = Arc::new(Mutex::new(CtxObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+
+        for _ in 0..3 {
+            assert_eq!(observable.send_to_all_with_ctx(&MyMessage::Msg("1"), &observable.next_context()), 2);
+        }
+
+        let contexts = ob1.lock().unwrap().contexts.clone();
+        assert_eq!(contexts.iter().map(|c| c.sequence).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(contexts.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+        // observers without an override are notified as usual
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(3usize));
+    }
+
+    #[test]
+    fn test_send_to_range() {
+        let mut observable = Observable::<MyMessage>::new();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let observers = order_observers(5, &log);
+        observers.iter().for_each(|o| {
+            observable.register(Arc::downgrade(o));
+        });
+
+        assert_eq!(observable.send_to_range(&MyMessage::Msg("1"), 1..3), 2);
+        assert_eq!(observable.send_to_range(&MyMessage::Msg("2"), 3..10), 2);
+        assert_eq!(observable.send_to_range(&MyMessage::Msg("3"), 7..), 0);
+        assert_eq!(observable.send_to_range(&MyMessage::Msg("4"), ..=0), 1);
+        assert_eq!(*log.lock().unwrap(), vec![1, 2, 3, 4, 0]);
+    }
+
+    #[test]
+    fn test_send_to_all_budgeted() {
+        let mut observable = Observable::<MyMessage>::new();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let observers = order_observers(4, &log);
+        observers.iter().for_each(|o| {
+            observable.register(Arc::downgrade(o));
+        });
+
+        assert_eq!(observable.send_to_all_budgeted(&MyMessage::Msg("1"), 2), 2);
+        assert_eq!(observable.send_to_all_budgeted(&MyMessage::Msg("2"), 2), 2);
+        assert_eq!(observable.send_to_all_budgeted(&MyMessage::Msg("3"), 3), 3);
+        assert_eq!(*log.lock().unwrap(), vec![0, 1, 2, 3, 0, 1, 2]);
+        assert_eq!(observable.send_to_all_budgeted(&MyMessage::Msg("4"), 10), 4);
+        assert_eq!(log.lock().unwrap()[7..], [3, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_send_to_next() {
+        let mut observable = Observable::<MyMessage>::new();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let observers = order_observers(3, &log);
+        observers.iter().for_each(|o| {
+            observable.register(Arc::downgrade(o));
+        });
+        let dead = order_observers(1, &log);
+        observable.register(Arc::downgrade(&dead[0]));
+        drop(dead);
+
+        let picked: Vec<_> = (0..4).map(|_| observable.send_to_next(&MyMessage::Msg("job"))).collect();
+        assert_eq!(picked, vec![Some(0), Some(1), Some(2), Some(0)]);
+        assert_eq!(*log.lock().unwrap(), vec![0, 1, 2, 0]);
+
+        drop(observers);
+        assert_eq!(observable.send_to_next(&MyMessage::Msg("job")), None);
+    }
+
+    struct StopObserver {
+        counter: usize,
+    }
+
+    impl Observer<MyMessage> for StopObserver {
+        fn notify(&mut self, _event: &MyMessage) {
+            self.counter += 1;
+        }
+        fn notify_stoppable(&mut self, event: &MyMessage) -> Propagation {
+            self.notify(event);
+            Propagation::Stop
+        }
+    }
+
+    #[test]
+    fn test_send_to_all_stoppable() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<StopObserver>> = Arc::new(Mutex::new(StopObserver { counter: 0 }));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        let ob3: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob3d: Arc<Mutex<dyn Observer<MyMessage>>> = ob3.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+        observable.register(Arc::downgrade(&ob3d));
+
+        assert_eq!(observable.send_to_all_stoppable(&MyMessage::Msg("1")), 2);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 3);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(2usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(2usize));
+        assert_eq!(ob3.lock().ok().map(|v| v.counter), Some(1usize));
+    }
+
+    #[test]
+    fn test_send_batch() {
+        let mut observable = Observable::<MyMessage>::new();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let observers = order_observers(2, &log);
+        observable.register(Arc::downgrade(&observers[0]));
+        observable.register(Arc::downgrade(&observers[1]));
+
+        let ob3: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob3d: Arc<Mutex<dyn Observer<MyMessage>>> = ob3.clone();
+        observable.register_once(Arc::downgrade(&ob3d));
+
+        assert_eq!(observable.send_batch(&[MyMessage::Msg("1"), MyMessage::Msg("2")]), 5);
+        assert_eq!(*log.lock().unwrap(), vec![0, 0, 1, 1]);
+        assert_eq!(ob3.lock().ok().map(|v| v.counter), Some(1usize));
+    }
+
+    #[test]
+    fn test_for_each_live() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob3: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob3d: Arc<Mutex<dyn Observer<MyMessage>>> = ob3.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2));
+        observable.register(Arc::downgrade(&ob3d));
+        observable.send_to_all(&MyMessage::Msg("1"));
+        observable.send_to_all(&MyMessage::Msg("2"));
+        drop(ob2);
+
+        let mut visited = Vec::new();
+        observable.for_each_live(|i, o| {
+            visited.push(i);
+            if let Some(o) = o.as_any_mut().and_then(|o| o.downcast_mut::<BeforeObserver>()) {
+                o.counter = 0;
+            }
+        });
+
+        assert_eq!(visited, vec![0, 2]);
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(0usize));
+        assert_eq!(ob3.lock().ok().map(|v| v.counter), Some(0usize));
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        observable.send_to_all(&MyMessage::Msg("1"));
+        let ob2: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        let ob3: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        observable.register(Arc::downgrade(&ob2d));
+        observable.register(Arc::downgrade(&ob3));
+        observable.send_to_all(&MyMessage::Msg("2"));
+        drop(ob3);
+
+        observable.retain(|o| {
+            o.as_any()
+                .and_then(|o| o.downcast_ref::<BeforeObserver>())
+                .is_some_and(|o| o.counter < 2)
+        });
+
+        assert_eq!(observable.len(), 1);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("3")), 1);
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(2usize));
+    }
+
+    #[test]
+    fn test_with_subscriber() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        observable.register(Arc::downgrade(&ob2));
+        observable.send_to_all(&MyMessage::Msg("1"));
+        drop(ob2);
+
+        let counter = |o: &mut dyn Observer<MyMessage>| {
+            o.as_any().and_then(|o| o.downcast_ref::<BeforeObserver>()).map(|o| o.counter)
+        };
+        assert_eq!(observable.with_subscriber(0, counter), Some(Some(1)));
+        assert_eq!(observable.with_subscriber(1, counter), None);
+        assert_eq!(observable.with_subscriber(2, counter), None);
+    }
+
+    #[test]
+    fn test_try_register() {
+        let mut observable = Observable::<MyMessage>::with_capacity(2);
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob3: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        assert!(observable.try_register(Arc::downgrade(&ob1)).is_ok());
+        assert!(observable.try_register(Arc::downgrade(&ob2)).is_ok());
+        assert_eq!(observable.try_register(Arc::downgrade(&ob3)).err(), Some(RegisterError::Full { capacity: 2, live: 2 }));
+
+        drop(ob1);
+        assert!(observable.try_register(Arc::downgrade(&ob3)).is_ok());
+        assert_eq!(observable.len(), 2);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 2);
+    }
+
+    struct ReentrantObserver {
+        source: Weak<Observable<MyMessage>>,
+        counter: usize,
+        nested: Option<Result<usize, ReentrantSend>>,
+    }
+
+    impl Observer<MyMessage> for ReentrantObserver {
+        fn notify(&mut self, event: &MyMessage) {
+            self.counter += 1;
+            self.nested = self.source.upgrade().map(|o| o.try_send_to_all(event));
+        }
+    }
+
+    #[test]
+    fn test_reentrant_send() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<ReentrantObserver>> = Arc::new(Mutex::new(ReentrantObserver {
+            source: Weak::new(),
+            counter: 0,
+            nested: None,
+        }));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+
+        let observable = Arc::new(observable);
+        ob1.lock().unwrap().source = Arc::downgrade(&observable);
+
+        assert_eq!(observable.try_send_to_all(&MyMessage::Msg("1")), Ok(2));
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 2);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(2usize));
+        assert_eq!(ob1.lock().ok().and_then(|v| v.nested), Some(Err(ReentrantSend)));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(2usize));
+    }
+
+    struct NestedObserver {
+        source: Weak<Observable<MyMessage>>,
+        send: fn(&Observable<MyMessage>) -> usize,
+        nested: Vec<usize>,
+    }
+
+    impl Observer<MyMessage> for NestedObserver {
+        fn notify(&mut self, _event: &MyMessage) {
+            if let Some(o) = self.source.upgrade() {
+                self.nested.push((self.send)(&o));
+            }
+        }
+    }
+
+    #[test]
+    fn test_reentrant_fan_out_sends() {
+        let sends: [fn(&Observable<MyMessage>) -> usize; 5] = [
+            |o| o.send_batch(&[MyMessage::Msg("1")]),
+            |o| o.send_filtered(&MyMessage::Msg("1"), |_| true),
+            |o| o.send_filtered_by(&MyMessage::Msg("1"), |_| true),
+            |o| o.send_to_all_stoppable(&MyMessage::Msg("1")),
+            |o| o.send_to_range(&MyMessage::Msg("1"), ..),
+        ];
+        for send in sends {
+            let mut observable = Observable::<MyMessage>::new();
+            let ob1 = Arc::new(Mutex::new(NestedObserver { source: Weak::new(), send, nested: Vec::new() }));
+            observable.register_concrete(&ob1);
+            let observable = Arc::new(observable);
+            ob1.lock().unwrap().source = Arc::downgrade(&observable);
+
+            // would deadlock on ob1's own mutex if the nested send went through
+            assert_eq!(send(&observable), 1);
+            assert_eq!(ob1.lock().ok().map(|v| v.nested.clone()), Some(vec![0]));
+        }
+    }
+
+    #[test]
+    fn test_reentrant_send_to_topic() {
+        let mut observable = Observable::<MyMessage>::new();
+        let ob1 = Arc::new(Mutex::new(NestedObserver {
+            source: Weak::new(),
+            send: |o| o.send_to_topic("a", &MyMessage::Msg("1")),
+            nested: Vec::new(),
+        }));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        observable.register_on("a", Arc::downgrade(&ob1d));
+        let observable = Arc::new(observable);
+        ob1.lock().unwrap().source = Arc::downgrade(&observable);
+
+        assert_eq!(observable.send_to_topic("a", &MyMessage::Msg("1")), 1);
+        assert_eq!(ob1.lock().ok().map(|v| v.nested.clone()), Some(vec![0]));
+    }
+
+    #[test]
+    fn test_default() {
+        // MyMessage is not Default, so this only compiles without a `T: Default` bound
+        #[derive(Default)]
+        struct Embedding {
+            observable: Observable<MyMessage>,
+        }
+
+        let embedding = Embedding::default();
+        assert!(embedding.observable.is_empty());
+        assert_eq!(embedding.observable.send_to_all(&MyMessage::Msg("1")), 0);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        observable.register(Arc::downgrade(&ob2));
+        assert_eq!(observable.stats(), ObservableStats::default());
+
+        observable.send_to_all(&MyMessage::Msg("1"));
+        drop(ob2);
+        observable.send_to_all(&MyMessage::Msg("2"));
+        observable.send_to(&MyMessage::Msg("3"), 0);
+        observable.send_batch(&[MyMessage::Msg("4"), MyMessage::Msg("5")]);
+
+        assert_eq!(observable.stats(), ObservableStats {
+            total_sent: 5,
+            total_delivered: 6,
+            total_dropped: 3,
+        });
+    }
+
+    #[test]
+    fn test_try_send_to() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob3: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        observable.register(Arc::downgrade(&ob2));
+        observable.register(Arc::downgrade(&ob3));
+        drop(ob2);
+        poison(&ob3);
+
+        let msg = MyMessage::Msg("1");
+        assert_eq!(observable.try_send_to(&msg, 0), Ok(()));
+        assert_eq!(observable.try_send_to(&msg, 1), Err(SendToError::Dropped));
+        assert_eq!(observable.try_send_to(&msg, 2), Err(SendToError::Poisoned));
+        assert_eq!(observable.try_send_to(&msg, 7), Err(SendToError::Index(7)));
+        assert_eq!(observable.send_to(&msg, 7), None);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let sub1 = observable.register(Arc::downgrade(&ob1d));
+        let owned = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register_owned(owned.clone());
+        observable.register(Weak::<Mutex<BeforeObserver>>::new());
+
+        assert_eq!(observable.clear(), 3);
+        assert_eq!(Arc::strong_count(&owned), 1);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 0);
+        assert!(!observable.unregister(sub1));
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(0usize));
+
+        observable.register(Arc::downgrade(&ob1d));
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 1);
+    }
+
+    #[test]
+    fn test_topics() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        let ob3: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob3d: Arc<Mutex<dyn Observer<MyMessage>>> = ob3.clone();
+        observable.register_on("left", Arc::downgrade(&ob1d));
+        observable.register_on("right", Arc::downgrade(&ob2d));
+        observable.register(Arc::downgrade(&ob3d));
+
+        assert_eq!(observable.send_to_topic("left", &MyMessage::Msg("1")), 1);
+        assert_eq!(observable.send_to_topic("right", &MyMessage::Msg("2")), 1);
+        assert_eq!(observable.send_to_topic("other", &MyMessage::Msg("3")), 0);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("4")), 3);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(2usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(2usize));
+        assert_eq!(ob3.lock().ok().map(|v| v.counter), Some(1usize));
+    }
+
+    #[test]
+    fn test_send_owned_to() {
+        // deliberately not `Clone`
+        #[derive(Debug, PartialEq)]
+        struct Payload(Vec<u8>);
+
+        #[derive(Default)]
+        struct Taker {
+            taken: Vec<Payload>,
+        }
+
+        impl Observer<Payload> for Taker {
+            fn notify(&mut self, _event: &Payload) {}
+            fn notify_owned(&mut self, event: Payload) {
+                self.taken.push(event);
+            }
+        }
+
+        let mut observable = Observable::<Payload>::new();
+        let ob1: Arc<Mutex<Taker>> = Arc::new(Mutex::new(Taker::default()));
+        let ob1d: Arc<Mutex<dyn Observer<Payload>>> = ob1.clone();
+        observable.register(Arc::downgrade(&ob1d));
+
+        assert_eq!(observable.send_owned_to(Payload(vec![1, 2]), 0), Ok(()));
+        assert_eq!(observable.send_owned_to(Payload(vec![3]), 1), Err(Payload(vec![3])));
+        assert_eq!(ob1.lock().ok().map(|v| v.taken.len()), Some(1usize));
+
+        drop(ob1d);
+        drop(ob1);
+        assert_eq!(observable.send_owned_to(Payload(vec![4]), 0), Err(Payload(vec![4])));
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register(Arc::downgrade(&ob1));
+        observable.register(Arc::downgrade(&ob2));
+        drop(ob2);
+
+        assert_eq!(format!("{:?}", observable), "Observable { len: 2, live: 1, dead: 1 }");
+    }
+
+    #[test]
+    fn test_notify_action_unsubscribe() {
+        #[derive(Default)]
+        struct OnceOnly {
+            counter: usize,
+        }
+
+        impl Observer<MyMessage> for OnceOnly {
+            fn notify(&mut self, _event: &MyMessage) {
+                self.counter += 1;
+            }
+            fn notify_action(&mut self, event: &MyMessage) -> NotifyAction {
+                self.notify(event);
+                NotifyAction::Unsubscribe
+            }
+        }
+
+        let mut observable = Observable::<MyMessage>::new();
+        let ob1: Arc<Mutex<OnceOnly>> = Arc::new(Mutex::new(OnceOnly::default()));
+        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
+        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2d));
+
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 2);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("2")), 1);
+        assert_eq!(observable.send_batch(&[MyMessage::Msg("3"), MyMessage::Msg("4")]), 2);
+        assert_eq!(observable.prune(), 1);
+
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(1usize));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(4usize));
+
+        // in a batch the observer is cut off right after asking to leave
+        let ob3: Arc<Mutex<OnceOnly>> = Arc::new(Mutex::new(OnceOnly::default()));
+        let ob3d: Arc<Mutex<dyn Observer<MyMessage>>> = ob3.clone();
+        observable.register(Arc::downgrade(&ob3d));
+        assert_eq!(observable.send_batch(&[MyMessage::Msg("5"), MyMessage::Msg("6")]), 3);
+        assert_eq!(ob3.lock().ok().map(|v| v.counter), Some(1usize));
+    }
+
+    #[test]
+    fn test_drain() {
+        let mut old = Observable::<MyMessage>::new();
+        let mut new = Observable::<MyMessage>::new();
+
+        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob3: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
+        old.register_concrete(&ob1);
+        old.register(Arc::downgrade(&ob2));
+        old.register_concrete(&ob3);
+        drop(ob2);
+
+        let drained = old.drain();
+        assert_eq!(drained.len(), 2);
+        assert_eq!(old.len(), 0);
+        assert_eq!(old.send_to_all(&MyMessage::Msg("1")), 0);
+
+        assert_eq!(new.register_many(drained).len(), 2);
+        assert_eq!(new.send_to_all(&MyMessage::Msg("2")), 2);
+        assert_eq!(ob1.lock().ok().map(|v| v.output.clone()), Some("2, World".to_string()));
+        assert_eq!(ob3.lock().ok().map(|v| v.output.clone()), Some("Hello, 2".to_string()));
+    }
+
+    #[test]
+    fn test_send_if_changed() {
+        let mut observable = Observable::<usize>::new();
+        let (ob1, ob1w) = test_util::recorder();
+        observable.register(ob1w);
+
+        assert_eq!(observable.send_if_changed(&1), 1);
+        assert_eq!(observable.send_if_changed(&1), 0);
+
+        let (ob2, ob2w) = test_util::recorder();
+        observable.register(ob2w);
+        assert_eq!(observable.send_if_changed(&1), 1);
+        assert_eq!(observable.send_if_changed(&2), 2);
+
+        assert_eq!(test_util::received(&ob1), vec![1, 2]);
+        assert_eq!(test_util::received(&ob2), vec![1, 2]);
+    }
+
+    struct Validator {
+        max_len: usize,
+    }
+
+    impl Observer<MyMessage> for Validator {
+        fn notify(&mut self, _event: &MyMessage) {}
+        fn handle(&mut self, event: &MyMessage) -> Result<(), String> {
+            let MyMessage::Msg(text) = event;
+            if text.len() > self.max_len {
+                return Err(format!("{:?} is longer than {}", text, self.max_len));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_send_collect() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1 = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2 = Arc::new(Mutex::new(Validator { max_len: 2 }));
+        let ob3: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        observable.register_concrete(&ob1);
+        observable.register(Arc::downgrade(&ob3));
+        observable.register_concrete(&ob2);
+        drop(ob3);
+
+        assert_eq!(observable.send_collect(&MyMessage::Msg("ok")), vec![(0, Ok(())), (2, Ok(()))]);
+        assert_eq!(
+            observable.send_collect(&MyMessage::Msg("long")),
+            vec![(0, Ok(())), (2, Err("\"long\" is longer than 2".to_string()))],
+        );
+        assert_eq!(ob1.lock().ok().map(|v| v.counter), Some(2usize));
+    }
+
+    #[test]
+    fn test_send_to_all_timeout() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1 = Arc::new(Mutex::new(BeforeObserver::default()));
+        let ob2 = Arc::new(Mutex::new(AfterObserver::default()));
+        observable.register_concrete(&ob1);
+        observable.register_concrete(&ob2);
+
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
+        let holder = {
+            let ob2 = ob2.clone();
+            std::thread::spawn(move || {
+                let _guard = ob2.lock().unwrap();
+                locked_tx.send(()).unwrap();
+                let _ = done_rx.recv();
+            })
+        };
+        locked_rx.recv().unwrap();
+        let start = Instant::now();
+        assert_eq!(observable.send_to_all_timeout(&MyMessage::Msg("1"), Duration::from_millis(20)), (1, vec![1]));
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        drop(done_tx);
+        holder.join().unwrap();
+
+        assert_eq!(observable.send_to_all_timeout(&MyMessage::Msg("2"), Duration::from_millis(20)), (2, vec![]));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(1usize));
+
+        assert_eq!(observable.send_to_all_timeout(&MyMessage::Msg("3"), Duration::MAX), (2, vec![]));
+        assert_eq!(ob2.lock().ok().map(|v| v.counter), Some(2usize));
+    }
+
+    #[test]
+    fn test_visit_all_mut() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let observers: Vec<_> = (0..3).map(|_| Arc::new(Mutex::new(BeforeObserver::default()))).collect();
+        observers.iter().for_each(|o| {
+            observable.register_concrete(o);
+        });
+        let ob4: Arc<Mutex<dyn Observer<MyMessage>>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        observable.register(Arc::downgrade(&ob4));
+        drop(ob4);
+        observable.send_to_all(&MyMessage::Msg("1"));
+
+        let skipped = observable.visit_all_mut(|_, o| {
+            if let Some(o) = o.as_any_mut().and_then(|o| o.downcast_mut::<BeforeObserver>()) {
+                o.counter = 0;
+            }
+        });
+        assert_eq!(skipped, vec![3]);
+        assert!(observers.iter().all(|o| o.lock().unwrap().counter == 0));
+    }
+
+    #[test]
+    fn test_poison_policy() {
+        use crate::test_util::{received, recorder};
+
+        for policy in [PoisonPolicy::Skip, PoisonPolicy::Recover, PoisonPolicy::Remove] {
+            let mut observable = Observable::<usize>::new();
+            observable.set_poison_policy(policy);
+            let (ob1, weak1) = recorder();
+            let (ob2, weak2) = recorder();
+            observable.register(weak1);
+            observable.register(weak2);
+            poison(&ob1);
+            assert!(ob1.is_poisoned());
+
+            let notified = observable.send_to(&1, 0).is_some();
+            let all = observable.send_to_all(&2);
+            assert_eq!(ob1.is_poisoned(), policy != PoisonPolicy::Recover);
+            ob1.clear_poison();
+            match policy {
+                PoisonPolicy::Skip => {
+                    assert!(!notified);
+                    assert_eq!(all, 1);
+                    assert_eq!(received(&ob1), Vec::<usize>::new());
+                    assert_eq!(observable.prune(), 0);
+                }
+                PoisonPolicy::Recover => {
+                    assert!(notified);
+                    assert_eq!(all, 2);
+                    assert_eq!(received(&ob1), vec![1, 2]);
+                    assert_eq!(observable.prune(), 0);
+                }
+                PoisonPolicy::Remove => {
+                    assert!(!notified);
+                    assert_eq!(all, 1);
+                    assert_eq!(received(&ob1), Vec::<usize>::new());
+                    assert_eq!(observable.live_count(), 1);
+                    assert_eq!(observable.prune(), 1);
+                }
+            }
+            assert_eq!(received(&ob2), vec![2]);
+        }
+    }
+
+    #[test]
+    fn test_send_with() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<String>::new();
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        let (dead, weak3) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+        observable.register(weak3);
+        drop(dead);
+
+        let calls = AtomicUsize::new(0);
+        let notified = observable.send_with(|i| {
+            calls.fetch_add(1, Ordering::Relaxed);
+            format!("hello #{}", i)
+        });
+        assert_eq!(notified, 2);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        assert_eq!(received(&ob1), vec!["hello #0".to_string()]);
+        assert_eq!(received(&ob2), vec!["hello #1".to_string()]);
+    }
+
+    #[test]
+    fn test_register_all() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<usize>::new();
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        let (ob3, weak3) = recorder();
+        let subscriptions = observable.register_all([weak1, weak2, weak3]);
+        assert_eq!(subscriptions.iter().map(|s| s.slot).collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        let (ob4, weak4) = recorder();
+        observable.extend(std::iter::once(weak4));
+
+        assert_eq!(observable.send_to_all(&1), 4);
+        for ob in [&ob1, &ob2, &ob3, &ob4] {
+            assert_eq!(received(ob), vec![1]);
+        }
+    }
+
+    #[test]
+    fn test_register_named() {
+        use crate::test_util::recorder;
+
+        let mut observable = Observable::<usize>::new();
+        let (_ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        let (ob3, weak3) = recorder();
+        observable.register_named("audit", weak1);
+        observable.register(weak2);
+        observable.register_named(String::from("metrics"), weak3);
+        assert_eq!(observable.subscriber_names(), vec![(0, "audit".to_string(), true), (2, "metrics".to_string(), true)]);
+
+        drop(ob3);
+        assert_eq!(observable.subscriber_names(), vec![(0, "audit".to_string(), true), (2, "metrics".to_string(), false)]);
+        drop(ob2);
+        assert_eq!(observable.prune(), 2);
+        assert_eq!(observable.subscriber_names(), vec![(0, "audit".to_string(), true)]);
+    }
+
+    #[test]
+    fn test_upgrade_all() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<usize>::new();
+        let (ob1, weak1) = recorder();
+        let (dead, weak2) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+        drop(dead);
+
+        let observers = observable.upgrade_all();
+        drop(observable);
+        assert_eq!(observers.len(), 1);
+        observers[0].lock().unwrap().notify(&1);
+        assert_eq!(received(&ob1), vec![1]);
+    }
+
+    #[test]
+    fn test_send_to_all_cancellable() {
+        let mut observable = Observable::<usize>::new();
+        let cancel = Arc::new(AtomicBool::new(false));
+        let notified = Arc::new(AtomicUsize::new(0));
+        for _ in 0..3 {
+            let (cancel, notified) = (cancel.clone(), notified.clone());
+            observable.register_fn(move |_| {
+                notified.fetch_add(1, Ordering::Relaxed);
+                cancel.store(true, Ordering::Release);
+            });
+        }
+
+        assert_eq!(observable.send_to_all_cancellable(&1, &cancel), 1);
+        assert_eq!(notified.load(Ordering::Relaxed), 1);
+        assert_eq!(observable.send_to_all_cancellable(&2, &cancel), 0);
+        assert_eq!(observable.send_to_all_cancellable(&3, &AtomicBool::new(false)), 3);
+    }
+
+    #[test]
+    fn test_register_unique_type() {
+        let mut observable = Observable::<MyMessage>::new();
+        let before = Arc::new(Mutex::new(BeforeObserver::default()));
+        let after = Arc::new(Mutex::new(AfterObserver::default()));
+        let second = Arc::new(Mutex::new(BeforeObserver::default()));
+
+        assert!(observable.register_unique_type(&before).is_ok());
+        assert!(observable.register_unique_type(&after).is_ok());
+        assert_eq!(observable.register_unique_type(&second).err(), Some(RegisterError::DuplicateType));
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 2);
+
+        drop(before);
+        assert!(observable.register_unique_type(&second).is_ok());
+        assert_eq!(observable.len(), 2);
+    }
+
+    #[test]
+    fn test_send_to_all_cloned() {
+        // counts its clones so the test can tell how many copies were made
+        struct Bytes {
+            data: Vec<u8>,
+            clones: Arc<AtomicUsize>,
+        }
+
+        impl Clone for Bytes {
+            fn clone(&self) -> Self {
+                self.clones.fetch_add(1, Ordering::Relaxed);
+                Bytes { data: self.data.clone(), clones: self.clones.clone() }
+            }
+        }
+
+        #[derive(Default)]
+        struct Reader {
+            total: usize,
+        }
+
+        impl Observer<Bytes> for Reader {
+            fn notify(&mut self, event: &Bytes) {
+                self.total += event.data.len();
+            }
+        }
+
+        #[derive(Default)]
+        struct Keeper {
+            kept: Vec<Vec<u8>>,
+        }
+
+        impl Observer<Bytes> for Keeper {
+            fn notify(&mut self, event: &Bytes) {
+                self.kept.push(event.data.clone());
+            }
+            fn notify_owned(&mut self, event: Bytes) {
+                self.kept.push(event.data);
+            }
+            fn wants_owned(&self) -> bool {
+                true
+            }
+        }
+
+        let mut observable = Observable::<Bytes>::new();
+        let reader1 = Arc::new(Mutex::new(Reader::default()));
+        let reader2 = Arc::new(Mutex::new(Reader::default()));
+        let keeper = Arc::new(Mutex::new(Keeper::default()));
+        observable.register_concrete(&reader1);
+        observable.register_concrete(&keeper);
+        observable.register_concrete(&reader2);
+
+        let clones = Arc::new(AtomicUsize::new(0));
+        let message = Bytes { data: vec![1, 2, 3], clones: clones.clone() };
+        assert_eq!(observable.send_to_all_cloned(&message), 3);
+        assert_eq!(clones.load(Ordering::Relaxed), 1);
+        assert_eq!(reader1.lock().unwrap().total, 3);
+        assert_eq!(reader2.lock().unwrap().total, 3);
+        assert_eq!(keeper.lock().unwrap().kept, vec![vec![1, 2, 3]]);
+    }
+
+    #[test]
+    fn test_query_all() {
+        struct Stock {
+            item: &'static str,
+            count: u32,
+        }
+
+        impl Observer<&'static str> for Stock {
+            fn notify(&mut self, _event: &&'static str) {}
+            fn query(&mut self, item: &&'static str) -> Option<Box<dyn Any + Send>> {
+                (*item == self.item).then(|| Box::new(self.count) as Box<dyn Any + Send>)
+            }
+        }
+
+        let mut observable = Observable::<&'static str>::new();
+        let apples = Arc::new(Mutex::new(Stock { item: "apple", count: 3 }));
+        let more_apples = Arc::new(Mutex::new(Stock { item: "apple", count: 5 }));
+        let pears = Arc::new(Mutex::new(Stock { item: "pear", count: 1 }));
+        observable.register_concrete(&apples);
+        observable.register_concrete(&pears);
+        observable.register_concrete(&more_apples);
+
+        assert_eq!(observable.query_all::<u32>(&"apple"), vec![3, 5]);
+        assert_eq!(observable.query_all::<u32>(&"plum"), Vec::<u32>::new());
+        assert_eq!(observable.query_all::<String>(&"pear"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_enqueue() {
+        // records every event and answers the first with a follow-up
+        struct Echo {
+            source: Weak<Observable<usize>>,
+            seen: Vec<usize>,
+            nested: Vec<usize>,
+        }
+
+        impl Observer<usize> for Echo {
+            fn notify(&mut self, event: &usize) {
+                self.seen.push(*event);
+                if let (1, Some(o)) = (*event, self.source.upgrade()) {
+                    self.nested.push(o.enqueue(2));
+                }
+            }
+        }
+
+        let mut observable = Observable::<usize>::new();
+        let echo = Arc::new(Mutex::new(Echo { source: Weak::new(), seen: Vec::new(), nested: Vec::new() }));
+        let (other, weak) = crate::test_util::recorder();
+        observable.register_concrete(&echo);
+        observable.register(weak);
+        let observable = Arc::new(observable);
+        echo.lock().unwrap().source = Arc::downgrade(&observable);
+
+        assert_eq!(observable.enqueue(1), 4);
+        assert_eq!(echo.lock().unwrap().seen, vec![1, 2]);
+        assert_eq!(echo.lock().unwrap().nested, vec![0]);
+        assert_eq!(crate::test_util::received(&other), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut observable = Observable::<usize>::new();
+        let (ob1, weak1) = crate::test_util::recorder();
+        let ob1: Arc<Mutex<dyn Observer<usize>>> = ob1;
+        let (_ob2, weak2) = crate::test_util::recorder();
+        assert!(!observable.contains(&weak1));
+
+        let subscription = observable.register(weak1.clone());
+        observable.register(weak2.clone());
+        assert!(observable.contains(&weak1));
+        assert!(observable.contains_arc(&ob1));
+
+        assert!(observable.unregister(subscription));
+        assert!(!observable.contains(&weak1));
+        assert!(!observable.contains_arc(&ob1));
+        assert!(observable.contains(&weak2));
+    }
+
+    #[test]
+    fn test_send_to_all_with() {
+        let mut observable = Observable::<usize>::new();
+        let (_ob1, weak1) = crate::test_util::recorder();
+        let (ob2, weak2) = crate::test_util::recorder();
+        let (ob3, weak3) = crate::test_util::recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+        observable.register(weak3);
+        drop(ob2);
+        poison(&ob3);
+
+        let mut outcomes = Vec::new();
+        assert_eq!(observable.send_to_all_with(&1, |i, result| outcomes.push((i, result))), 1);
+        assert_eq!(outcomes, vec![
+            (0, DeliveryResult::Delivered),
+            (1, DeliveryResult::Dropped),
+            (2, DeliveryResult::Poisoned),
+        ]);
+    }
+
+    #[test]
+    fn test_send_to_all_retry() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<usize>::new();
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+
+        // the lock is released by the first wait, so the first retry gets through
+        let mut held = Some(ob1.lock().unwrap());
+        let mut waits = 0;
+        let (notified, skipped) = observable.send_to_all_retry_with(&1, 3, || {
+            waits += 1;
+            held = None;
+        });
+        assert_eq!((notified, skipped), (2, vec![]));
+        assert_eq!(waits, 1);
+        assert_eq!(received(&ob1), vec![1]);
+        assert_eq!(received(&ob2), vec![1]);
+
+        let _held = ob2.lock().unwrap();
+        let mut waits = 0;
+        assert_eq!(observable.send_to_all_retry_with(&2, 2, || waits += 1), (1, vec![1]));
+        assert_eq!(waits, 2);
+        assert_eq!(observable.send_to_all_retry(&3, 0, Duration::from_secs(60)), (1, vec![1]));
+    }
+
+    #[test]
+    fn test_send_dedup() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<&'static str>::new();
+        let (ob1, weak1) = recorder();
+        observable.register(weak1);
+
+        assert!(observable.send_dedup(1, &"a"));
+        assert!(!observable.send_dedup(1, &"a again"));
+        assert!(observable.send_dedup(EventId(2), &"b"));
+
+        observable.set_dedup_window(1);
+        assert!(observable.send_dedup(1, &"a, out of the window"));
+        assert!(!observable.send_dedup(1, &"a once more"));
+        assert_eq!(received(&ob1), vec!["a", "b", "a, out of the window"]);
+    }
+
+    #[test]
+    fn test_groups() {
+        use crate::test_util::{received, recorder};
+
+        const AUDIO: GroupId = GroupId(1);
+        let mut observable = Observable::<&'static str>::new();
+        let (audio, weak1) = recorder();
+        let (other, weak2) = recorder();
+        let handle = observable.register_in_group(AUDIO, weak1);
+        observable.register(weak2);
+
+        assert_eq!(observable.send_to_all(&"a"), 2);
+        observable.set_group_enabled(AUDIO, false);
+        assert!(!observable.is_group_enabled(AUDIO));
+        assert_eq!(observable.send_to_all(&"b"), 1);
+        assert_eq!(observable.send_to_next(&"c"), Some(1));
+        assert_eq!(observable.send_to_next(&"d"), Some(1));
+        // still reachable directly, and still registered
+        assert_eq!(observable.send_to_handle(&"e", &handle), Ok(()));
+        assert_eq!(observable.live_count(), 2);
+
+        observable.set_group_enabled(AUDIO, true);
+        assert_eq!(observable.send_to_all(&"f"), 2);
+        assert_eq!(received(&audio), vec!["a", "e", "f"]);
+        assert_eq!(received(&other), vec!["a", "b", "c", "d", "f"]);
+        assert_eq!(observable.stats().total_dropped, 0);
+    }
+
+    #[test]
+    fn test_flush_all() {
+        // holds events back until flushed
+        #[derive(Default)]
+        struct Batcher {
+            pending: Vec<usize>,
+            committed: Vec<usize>,
         }
+
+        impl Observer<usize> for Batcher {
+            fn notify(&mut self, event: &usize) {
+                self.pending.push(*event);
+            }
+            fn flush(&mut self) {
+                self.committed.append(&mut self.pending);
+            }
+        }
+
+        let mut observable = Observable::<usize>::new();
+        let batcher = Arc::new(Mutex::new(Batcher::default()));
+        let dropped = Arc::new(Mutex::new(Batcher::default()));
+        observable.register_concrete(&batcher);
+        observable.register_concrete(&dropped);
+        drop(dropped);
+
+        observable.send_to_all(&1);
+        observable.send_to_all(&2);
+        assert!(batcher.lock().unwrap().committed.is_empty());
+        assert_eq!(observable.flush_all(), 1);
+        observable.send_to_all(&3);
+        let batcher = batcher.lock().unwrap();
+        assert_eq!(batcher.committed, vec![1, 2]);
+        assert_eq!(batcher.pending, vec![3]);
+    }
+
+    #[test]
+    fn test_send_or_err() {
+        use crate::test_util::recorder;
+
+        let mut observable = Observable::<u8>::new();
+        assert_eq!(observable.send_or_err(&1), Err(NoSubscribers));
+        let (ob1, weak1) = recorder();
+        observable.register(weak1);
+        assert_eq!(observable.send_or_err(&2), Ok(1));
+        drop(ob1);
+        assert_eq!(observable.send_or_err(&3), Err(NoSubscribers));
+    }
+
+    #[test]
+    fn test_send_to_all_matches_detailed() {
+        use crate::test_util::recorder;
+
+        // a one-shot, a poisoned and a dropped observer among plain ones
+        let setup = || {
+            let (mut observers, weaks): (Vec<_>, Vec<_>) = (0..6).map(|_| recorder::<u8>()).unzip();
+            let mut observable = Observable::new();
+            observable.register_once(weaks[0].clone());
+            observable.register_all(weaks[1..].iter().cloned());
+            poison(&observers[1]);
+            observers.remove(2);
+            (observable, observers)
+        };
+        let (plain, _plain_observers) = setup();
+        let (detailed, _detailed_observers) = setup();
+
+        for message in 0..3 {
+            let expected = detailed.send_to_all_detailed(&message)
+                .into_iter()
+                .filter(|r| *r == DeliveryResult::Delivered)
+                .count();
+            assert_eq!(plain.send_to_all(&message), expected);
+        }
+        assert_eq!(plain.stats(), detailed.stats());
     }
-    pub(crate) fn register(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) {
-        self.subscribers.push(observer)
+
+    #[test]
+    fn test_pause_resume() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<u32>::new();
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+
+        observable.pause();
+        assert_eq!(observable.send_to_all(&1), 0);
+        assert_eq!(observable.send_to_all(&2), 0);
+        assert!(received(&ob1).is_empty());
+        assert_eq!(observable.resume(), 4);
+        assert!(!observable.is_paused());
+        assert_eq!(observable.send_to_all(&3), 2);
+        assert_eq!(received(&ob1), vec![1, 2, 3]);
+        assert_eq!(received(&ob2), vec![1, 2, 3]);
+        assert_eq!(observable.resume(), 0);
+    }
+
+    #[test]
+    fn test_register_positioned() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let tagged = |tag: &'static str| {
+            let log = log.clone();
+            into_dyn(Arc::new(Mutex::new(FnObserver::new(move |_: &u8| log.lock().unwrap().push(tag)))))
+        };
+        let observers = [tagged("audit"), tagged("normal"), tagged("urgent"), tagged("first")];
+        let mut observable = Observable::new();
+        observable.register_positioned(Arc::downgrade(&observers[0]), 100, DeliveryPosition::Last);
+        observable.register(Arc::downgrade(&observers[1]));
+        observable.register_with_priority(Arc::downgrade(&observers[2]), 10);
+        observable.register_positioned(Arc::downgrade(&observers[3]), -10, DeliveryPosition::First);
+
+        observable.send_to_all(&0);
+        assert_eq!(*log.lock().unwrap(), vec!["first", "urgent", "normal", "audit"]);
     }
-    pub(crate) fn send_to_all(&self, message: &T) -> usize {
-        (0..self.subscribers.len()).fold(0, |acc, i|
-            match self.send_to(message, i) {
-                Some(_) => acc+1,
-                None => acc,
+
+    #[test]
+    fn test_send_to_all_deadline() {
+        use crate::test_util::{received, recorder};
+
+        // takes 10ms of the fake clock per event
+        struct Slow(Arc<Mutex<Instant>>);
+
+        impl Observer<u8> for Slow {
+            fn notify(&mut self, _: &u8) {
+                *self.0.lock().unwrap() += Duration::from_millis(10);
             }
-        )
+        }
+
+        let start = Instant::now();
+        let clock = Arc::new(Mutex::new(start));
+        let slow = Arc::new(Mutex::new(Slow(clock.clone())));
+        let mut observable = Observable::new();
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        observable.register(weak1);
+        observable.register_concrete(&slow);
+        observable.register_concrete(&slow);
+        observable.register(weak2);
+        let now = || *clock.lock().unwrap();
+
+        let deadline = start + Duration::from_millis(5);
+        assert_eq!(observable.send_to_all_deadline_with(&1, deadline, now), (2, vec![2, 3]));
+        assert_eq!(received(&ob1), vec![1]);
+        assert!(received(&ob2).is_empty());
+
+        // an observer locked elsewhere is waited for only until the deadline
+        let deadline = now() + Duration::from_millis(5);
+        let _held = slow.lock().unwrap();
+        let mut tries = 0;
+        let ticking = || {
+            tries += 1;
+            *clock.lock().unwrap() += Duration::from_millis(1);
+            now()
+        };
+        assert_eq!(observable.send_to_all_deadline_with(&2, deadline, ticking), (1, vec![1, 2, 3]));
+        // one check for the first, four spent waiting on the locked one, one each after
+        assert_eq!(tries, 7);
     }
-    pub(crate) fn send_to(&self, message: &T, i: usize) -> Option<()> {
-        self.subscribers.get(i)
-            .and_then(|s|
-                s.upgrade()
-            ).and_then(|s| {
-                s.lock().ok().as_mut().map(|s| {
-                    s.notify(message);
-                })
-            })
+
+    #[test]
+    fn test_subscriber_refcounts() {
+        use crate::test_util::recorder;
+
+        let mut observable = Observable::<u8>::new();
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+        observable.register_fn(|_| {});
+        drop(ob2);
+        assert_eq!(observable.subscriber_refcounts(), vec![(0, Some((1, 1))), (1, None), (2, Some((1, 0)))]);
+
+        let held = observable.upgrade_all();
+        assert_eq!(observable.subscriber_refcounts(), vec![(0, Some((2, 1))), (1, None), (2, Some((2, 0)))]);
+        drop((held, ob1));
+        assert_eq!(observable.subscriber_refcounts()[0], (0, None));
     }
-}
 
-pub(crate) trait Observer<T> {
-    fn notify(&mut self, event: &T);
-}
+    #[test]
+    fn test_shutdown() {
+        use crate::test_util::{received, recorder};
 
+        let mut observable = Observable::<&'static str>::new();
+        let (ob1, weak1) = recorder();
+        observable.register(weak1.clone());
+        observable.register_fn(|_| {});
+        observable.pause();
+        observable.send_to_all(&"last update");
 
-#[cfg(test)]
-mod tests {
+        assert_eq!(observable.shutdown(&"closing"), 2);
+        assert!(observable.is_closed());
+        assert!(observable.is_empty());
+        assert_eq!(received(&ob1), vec!["last update", "closing"]);
 
-    use crate::{*};
+        assert_eq!(observable.try_register(weak1.clone()).err(), Some(RegisterError::Closed));
+        let handle = observable.register(weak1);
+        assert!(observable.is_empty());
+        assert_eq!(observable.send_to_handle(&"after", &handle), Err(SendError::StaleHandle));
+        assert!(!observable.unregister(handle));
+        assert_eq!(observable.send_to_all(&"after"), 0);
+        assert_eq!(received(&ob1).len(), 2);
+    }
 
-    #[derive(PartialEq, Debug)]
-    enum MyMessage {
-        Msg(&'static str),
+    #[test]
+    fn test_merge_into() {
+        use crate::test_util::{received, recorder};
+
+        let mut a = Observable::<u8>::new();
+        let mut b = Observable::<u8>::new();
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        let (ob3, weak3) = recorder();
+        let (dead, weak4) = recorder();
+        let old = a.register_named("first", weak1);
+        a.register_with_priority(weak2, 5);
+        a.register(weak4);
+        b.register(weak3);
+        drop(dead);
+
+        let moved = a.merge_into(&mut b);
+        assert_eq!(moved.len(), 2);
+        assert!(a.is_empty());
+        assert!(!a.unregister(old));
+        assert_eq!(b.subscriber_names(), vec![(moved[1].slot, "first".to_string(), true)]);
+
+        assert_eq!(b.send_to_all(&1), 3);
+        for observer in [&ob1, &ob2, &ob3] {
+            assert_eq!(received(observer), vec![1]);
+        }
+        assert_eq!(b.live_count(), 3);
+        // the higher priority came along too
+        assert_eq!(b.entries().next().map(|(i, _)| i), Some(moved[0].slot));
     }
 
-    #[derive(Default)]
-    struct BeforeObserver {
-        output: String,
-        counter: usize,
+    static HANDLED: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+
+    fn handle(e: &MyMessage) {
+        let MyMessage::Msg(text) = e;
+        HANDLED.lock().unwrap().push(text);
     }
 
-    impl Observer<MyMessage> for BeforeObserver {
-        fn notify(&mut self, event: &MyMessage) {
-            self.counter += 1;
-            self.output = match event {
-                MyMessage::Msg(str) => format!("{}, World", str),
-            };
+    #[test]
+    fn test_fn_pointer_observers() {
+        let mut observable = Observable::new();
+        let seen = Arc::new(Mutex::new(0));
+        let counter = seen.clone();
+        let handlers = [
+            BoxFnObserver::new(move |_: &MyMessage| *counter.lock().unwrap() += 1),
+            BoxFnObserver::new(|_| {}),
+        ];
+        observable.register_owned(Arc::new(Mutex::new(FnPtrObserver(handle))));
+        observable.register_owned(Arc::new(Mutex::new(FnPtrObserver::from(handle as fn(&MyMessage)))));
+        for handler in handlers {
+            observable.register_owned(Arc::new(Mutex::new(handler)));
         }
+
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("1")), 4);
+        assert_eq!(*HANDLED.lock().unwrap(), vec!["1", "1"]);
+        assert_eq!(*seen.lock().unwrap(), 1);
     }
 
-    #[derive(Default)]
-    struct AfterObserver {
-        output: String,
-        counter: usize,
+    #[test]
+    fn test_auto_prune_after() {
+        use crate::test_util::recorder;
+
+        let mut observable = Observable::<u8>::builder().auto_prune_after(2).build();
+        let (_ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        let (ob3, weak3) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+        observable.register(weak3);
+        drop((ob2, ob3));
+
+        observable.send_to_all(&1);
+        assert_eq!(observable.send_to_next(&2), Some(0));
+        assert_eq!(observable.len(), 3);
+        observable.send_to_all(&3);
+        // the second send made a prune due, which the next `&mut self` call carries out
+        assert_eq!(observable.send_to_next(&4), Some(0));
+        assert_eq!(observable.len(), 1);
+
+        observable.set_auto_prune_after(0);
+        let (ob4, weak4) = recorder();
+        observable.register(weak4);
+        drop(ob4);
+        for n in 0..10 {
+            observable.send_to_all(&n);
+        }
+        observable.send_if_changed(&0);
+        assert_eq!(observable.len(), 2);
     }
 
-    impl Observer<MyMessage> for AfterObserver {
-        fn notify(&mut self, event: &MyMessage) {
-            self.counter += 1;
-            self.output = match event {
-                MyMessage::Msg(str) => format!("Hello, {}", str),
+    #[test]
+    fn test_send_prioritized() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<&'static str>::new();
+        let (ob1, weak1) = recorder();
+        observable.register(weak1);
+
+        let held = ob1.lock().unwrap();
+        assert_eq!(observable.send_prioritized(&"low", EventPriority::Low), 0);
+        std::thread::scope(|scope| {
+            let sender = scope.spawn(|| observable.send_prioritized(&"high", EventPriority::High));
+            drop(held);
+            assert_eq!(sender.join().unwrap(), 1);
+        });
+        assert_eq!(received(&ob1), vec!["high"]);
+    }
+
+    #[test]
+    fn test_find_live() {
+        struct Counter(u32);
+
+        impl Observer<u32> for Counter {
+            fn notify(&mut self, event: &u32) {
+                self.0 += event;
+            }
+            fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+                Some(self)
             }
         }
+
+        let mut observable = Observable::new();
+        let observers = [1, 5, 10, 20].map(|n| Arc::new(Mutex::new(Counter(n))));
+        for observer in &observers {
+            observable.register_concrete(observer);
+        }
+        let exceeds = |limit| move |o: &mut dyn Observer<u32>| {
+            o.as_any_mut().and_then(|o| o.downcast_mut::<Counter>()).is_some_and(|c| c.0 > limit)
+        };
+
+        assert_eq!(observable.find_live(exceeds(7)), Some(2));
+        assert_eq!(observable.find_live(exceeds(50)), None);
+        // stops at the first match, so a later locked observer is never waited for
+        let _held = observers[3].lock().unwrap();
+        assert_eq!(observable.find_live(exceeds(0)), Some(0));
     }
 
     #[test]
-    fn test_observable() {
-        let mut observable = Observable::<MyMessage>::new();
+    fn test_send_to_all_tracked() {
+        use crate::test_util::recorder;
 
-        let ob1: Arc<Mutex<BeforeObserver>> = Arc::new(Mutex::new(BeforeObserver::default()));
+        // fails its first delivery only
+        struct Flaky(bool);
 
-        // cannot directly cast type (requires unstable rust)
-        // see https://github.com/rust-lang/rfcs/blob/master/text/0982-dst-coercion.md
-        let ob1d: Arc<Mutex<dyn Observer<MyMessage>>> = ob1.clone();
-        observable.register(Arc::downgrade(&ob1d));
-        observable.send_to_all(&MyMessage::Msg("1"));
-        
-        {
-            let lock1 = ob1.lock();
-            let ob1 = lock1.as_ref().ok();
-            assert_eq!(ob1.map(|v| v.counter), Some(1usize));
-            assert_eq!(ob1.map(|v| &v.output[..]), Some(&format!("1, World")[..]));
+        impl Observer<u8> for Flaky {
+            fn notify(&mut self, _: &u8) {}
+            fn notify_ack(&mut self, _: &u8) -> bool {
+                std::mem::replace(&mut self.0, true)
+            }
         }
 
-        let ob2: Arc<Mutex<AfterObserver>> = Arc::new(Mutex::new(AfterObserver::default()));
-        let ob2d: Arc<Mutex<dyn Observer<MyMessage>>> = ob2.clone();
-        observable.register(Arc::downgrade(&ob2d));
-        observable.send_to_all(&MyMessage::Msg("2"));
+        let mut observable = Observable::new();
+        let (_ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        let flaky = Arc::new(Mutex::new(Flaky(false)));
+        observable.register(weak1);
+        observable.register_concrete(&flaky);
+        observable.register(weak2);
+        drop(ob2);
 
-        {
-            let lock1 = ob1.lock();
-            let ob1 = lock1.as_ref().ok();
-            let lock2 = ob2.lock();
-            let ob2 = lock2.as_ref().ok();
-            assert_eq!(ob1.map(|v| v.counter), Some(2usize));
-            assert_eq!(ob1.map(|v| &v.output[..]), Some(&format!("2, World")[..]));
-            assert_eq!(ob2.map(|v| v.counter), Some(1usize));
-            assert_eq!(ob2.map(|v| &v.output[..]), Some(&format!("Hello, 2")[..]));
+        let report = observable.send_to_all_tracked(&1);
+        assert_eq!(report, AckReport { acked: vec![0], nacked: vec![1], failed: vec![2] });
+        for i in report.nacked {
+            assert_eq!(observable.send_to(&1, i), Some(()));
+        }
+        assert_eq!(observable.send_to_all_tracked(&2).nacked, Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_split() {
+        // keeps what it is sent, and whether it belongs to the first shard
+        struct Shard(bool, Vec<u32>);
+
+        impl Observer<u32> for Shard {
+            fn notify(&mut self, event: &u32) {
+                self.1.push(*event);
+            }
+            fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+                Some(self)
+            }
         }
 
-        observable.send_to(&MyMessage::Msg("3"), 1);
+        let mut observable = Observable::new();
+        let shards = [true, false, true, false].map(|first| Arc::new(Mutex::new(Shard(first, Vec::new()))));
+        for shard in &shards {
+            observable.register_concrete(shard);
+        }
+        let dead = Arc::new(Mutex::new(Shard(true, Vec::new())));
+        observable.register_concrete(&dead);
+        drop(dead);
 
+        let (first, second) = observable.split(|o| {
+            o.as_any_mut().and_then(|o| o.downcast_mut::<Shard>()).is_some_and(|s| s.0)
+        });
+        assert_eq!((first.len(), second.len()), (2, 2));
+        assert_eq!(first.send_to_all(&1), 2);
+        assert_eq!(second.send_to_all(&2), 2);
+        let seen = shards.map(|s| s.lock().unwrap().1.clone());
+        assert_eq!(seen, [vec![1], vec![2], vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn test_send_to_all_indexed() {
+        #[derive(Default)]
+        struct Stripe(Vec<(usize, usize)>);
+
+        impl Observer<u8> for Stripe {
+            fn notify(&mut self, _: &u8) {}
+            fn notify_indexed(&mut self, _: &u8, index: usize, total: usize) {
+                self.0.push((index, total));
+            }
+        }
+
+        let mut observable = Observable::new();
+        let stripes = [(); 3].map(|_| Arc::new(Mutex::new(Stripe::default())));
+        let dead = Arc::new(Mutex::new(Stripe::default()));
+        observable.register_concrete(&stripes[0]);
+        observable.register_concrete(&dead);
+        observable.register_concrete(&stripes[1]);
+        observable.register_concrete(&stripes[2]);
+        drop(dead);
+
+        assert_eq!(observable.send_to_all_indexed(&1), 3);
+        let seen = stripes.map(|s| s.lock().unwrap().0.clone());
+        assert_eq!(seen, [vec![(0, 3)], vec![(1, 3)], vec![(2, 3)]]);
+    }
+
+    #[test]
+    fn test_drain_filter() {
+        struct Counter(u32);
+
+        impl Observer<u32> for Counter {
+            fn notify(&mut self, event: &u32) {
+                self.0 += event;
+            }
+            fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+                Some(self)
+            }
+        }
+
+        let mut observable = Observable::new();
+        let observers = [0, 3, 0, 5].map(|n| Arc::new(Mutex::new(Counter(n))));
+        for observer in &observers {
+            observable.register_concrete(observer);
+        }
+        let dead = Arc::new(Mutex::new(Counter(0)));
+        observable.register_concrete(&dead);
+        drop(dead);
+
+        let idle = observable.drain_filter(|o| {
+            o.as_any_mut().and_then(|o| o.downcast_mut::<Counter>()).is_some_and(|c| c.0 == 0)
+        });
+        assert_eq!(idle.len(), 2);
+        assert!(idle.iter().all(|o| o.upgrade().is_some()));
+        assert_eq!(observable.len(), 2);
+
+        assert_eq!(observable.send_to_all(&1), 2);
+        let counts = observers.each_ref().map(|o| o.lock().unwrap().0);
+        assert_eq!(counts, [0, 4, 0, 6]);
+
+        let mut other = Observable::new();
+        other.register_many(idle);
+        assert_eq!(other.send_to_all(&2), 2);
+        assert_eq!(observers[0].lock().unwrap().0, 2);
+    }
+
+    #[test]
+    fn test_last_prune_stats() {
+        use crate::test_util::recorder;
+
+        let mut observable = Observable::new();
+        let (_ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        let (ob3, weak3) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+        observable.register(weak3);
+        assert_eq!(observable.last_prune_stats(), PruneStats::default());
+
+        drop(ob2);
+        for i in 0..4 {
+            observable.send_to_all(&i);
+        }
+        drop(ob3);
+        observable.send_to_all(&4);
+        assert_eq!(observable.prune(), 2);
+        assert_eq!(observable.last_prune_stats(), PruneStats { pruned: 2, max_stale_sends: 5 });
+
+        observable.send_to_all(&5);
+        observable.prune();
+        assert_eq!(observable.last_prune_stats(), PruneStats { pruned: 0, max_stale_sends: 0 });
+    }
+
+    #[test]
+    fn test_unsized_events() {
+        #[derive(Default)]
+        struct Lines(Vec<String>);
+
+        impl Observer<str> for Lines {
+            fn notify(&mut self, line: &str) {
+                self.0.push(line.to_owned());
+            }
+        }
+
+        let mut observable = Observable::<str>::new();
+        let first = Arc::new(Mutex::new(Lines::default()));
+        let second = Arc::new(Mutex::new(Lines::default()));
+        observable.register_concrete(&first);
+        observable.register_concrete(&second);
+        observable.register_fn(|line: &str| assert!(!line.is_empty()));
+
+        assert_eq!(observable.send_to_all("hello"), 3);
+        let owned = String::from("world");
+        assert_eq!(observable.send_to_all(&owned[..]), 3);
+        assert_eq!(first.lock().unwrap().0, ["hello", "world"]);
+        assert_eq!(second.lock().unwrap().0, ["hello", "world"]);
+
+        let bytes = Observable::<[u8]>::new();
+        assert_eq!(bytes.send_to_all(&[1, 2, 3]), 0);
+    }
+
+    #[test]
+    fn test_dead_letter() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::new();
+        let (dead_letters, weak) = recorder();
+        observable.set_dead_letter(weak);
+        assert_eq!(observable.len(), 0);
+
+        assert_eq!(observable.send_to_all(&1), 0);
+        assert_eq!(received(&dead_letters), vec![1]);
+
+        let (ob1, weak1) = recorder();
+        observable.register(weak1);
+        assert_eq!(observable.send_to_all(&2), 1);
+        drop(ob1);
+        assert_eq!(observable.send_to_all(&3), 0);
+        assert_eq!(received(&dead_letters), vec![1, 3]);
+        assert_eq!(observable.stats().total_delivered, 1);
+    }
+
+    #[test]
+    fn test_send_and_prune() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::new();
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        let (ob3, weak3) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+        observable.register(weak3);
+        drop(ob2);
+
+        assert_eq!(observable.send_and_prune(&1), 2);
+        assert_eq!(observable.len(), 2);
+        assert_eq!(observable.last_prune_stats(), PruneStats { pruned: 1, max_stale_sends: 1 });
+        assert_eq!(observable.stats().total_dropped, 1);
+
+        // the survivors kept their slots
+        assert_eq!(observable.send_to(&2, 2), Some(()));
+        assert_eq!(received(&ob1), vec![1]);
+        assert_eq!(received(&ob3), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_send_to_weighted_random() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::new();
+        observable.rng = order::Random::with_seed(0x5eed);
+        let (canary, weak1) = recorder();
+        let (stable, weak2) = recorder();
+        let (dead, weak3) = recorder();
+        let (never, weak4) = recorder();
+        observable.register(weak1);
+        observable.register_weighted(9.0, weak2);
+        observable.register_weighted(100.0, weak3);
+        observable.register_weighted(0.0, weak4);
+        drop(dead);
+
+        for i in 0..1000 {
+            assert!(observable.send_to_weighted_random(&i).is_some_and(|n| n < 2));
+        }
+        // 10% and 90% of the sends ± 3%, the dead subscriber's weight aside
+        let (canary, stable) = (received(&canary).len(), received(&stable).len());
+        assert_eq!(canary + stable, 1000);
+        assert!((70..=130).contains(&canary), "{}", canary);
+        assert!(received(&never).is_empty());
+    }
+
+    #[test]
+    fn test_on_first() {
+        #[derive(Default)]
+        struct Lazy {
+            setup: Vec<u32>,
+            seen: Vec<u32>,
+        }
+
+        impl Observer<u32> for Lazy {
+            fn on_first(&mut self, event: &u32) {
+                self.setup.push(*event);
+            }
+            fn notify(&mut self, event: &u32) {
+                self.seen.push(*event);
+            }
+        }
+
+        let mut observable = Observable::new();
+        let lazy = Arc::new(Mutex::new(Lazy::default()));
+        observable.register_concrete(&lazy);
+        observable.send_to_all(&1);
+        observable.send_to_all(&2);
+        assert_eq!(observable.send_to(&3, 0), Some(()));
         {
-            let lock1 = ob1.lock();
-            let ob1 = lock1.as_ref().ok();
-            let lock2 = ob2.lock();
-            let ob2 = lock2.as_ref().ok();
-            assert_eq!(ob1.map(|v| v.counter), Some(2usize));
-            assert_eq!(ob1.map(|v| &v.output[..]), Some(&format!("2, World")[..]));
-            assert_eq!(ob2.map(|v| v.counter), Some(2usize));
-            assert_eq!(ob2.map(|v| &v.output[..]), Some(&format!("Hello, 3")[..]));
+            let lazy = lazy.lock().unwrap();
+            assert_eq!((&lazy.setup[..], &lazy.seen[..]), (&[1][..], &[1, 2, 3][..]));
+        }
+
+        // a second registration starts over
+        observable.register_concrete(&lazy);
+        observable.send_to_all_tracked(&4);
+        let lazy = lazy.lock().unwrap();
+        assert_eq!((&lazy.setup[..], &lazy.seen[..]), (&[1, 4][..], &[1, 2, 3, 4, 4][..]));
+    }
+
+    #[test]
+    fn test_send_to_indices() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::new();
+        let recorders: Vec<_> = (0..4).map(|_| recorder()).collect();
+        for (_, weak) in &recorders {
+            observable.register(weak.clone());
+        }
+
+        assert_eq!(observable.send_to_indices(&1, &[0, 2]), 2);
+        assert_eq!(observable.send_to_indices(&2, &[2, 2, 9, 3]), 2);
+        let seen: Vec<_> = recorders.iter().map(|(ob, _)| received(ob)).collect();
+        assert_eq!(seen, vec![vec![1], vec![], vec![1, 2], vec![2]]);
+    }
+
+    #[test]
+    fn test_try_register_capacity_edges() {
+        use crate::test_util::{received, recorder};
+
+        let (ob, weak) = recorder();
+        let mut none = Observable::<i32>::with_capacity(0);
+        for _ in 0..2 {
+            assert_eq!(none.try_register(weak.clone()).err(), Some(RegisterError::Full { capacity: 0, live: 0 }));
+        }
+        assert!(none.is_empty());
+        assert_eq!(
+            RegisterError::Full { capacity: 0, live: 0 }.to_string(),
+            "observable is at capacity: 0 live subscribers, capacity 0"
+        );
+
+        // exactly full, with one of the entries dead: the dead one makes room
+        let mut observable = Observable::<i32>::with_capacity(3);
+        let (_live1, weak1) = recorder();
+        let (dead, dead_weak) = recorder();
+        let (_live2, weak2) = recorder();
+        for w in [weak1, dead_weak, weak2] {
+            assert!(observable.try_register(w).is_ok());
+        }
+        assert_eq!(observable.try_register(weak.clone()).err(), Some(RegisterError::Full { capacity: 3, live: 3 }));
+        drop(dead);
+        assert!(observable.try_register(weak.clone()).is_ok());
+        assert_eq!(observable.len(), 3);
+        assert_eq!(observable.try_register(weak.clone()).err(), Some(RegisterError::Full { capacity: 3, live: 3 }));
+
+        observable.clear();
+        for _ in 0..3 {
+            assert!(observable.try_register(weak.clone()).is_ok());
+        }
+        assert_eq!(observable.try_register(weak).err(), Some(RegisterError::Full { capacity: 3, live: 3 }));
+        assert_eq!(observable.send_to_all(&1), 3);
+        assert_eq!(received(&ob), vec![1, 1, 1]);
+    }
+
+    #[test]
+    fn test_send_to_all_returning() {
+        struct Total(u32);
+
+        impl Observer<u32> for Total {
+            fn notify(&mut self, event: &u32) {
+                self.0 += event;
+            }
+            fn as_any(&self) -> Option<&dyn Any> {
+                Some(self)
+            }
+        }
+
+        let mut observable = Observable::new();
+        let totals = [3, 5].map(|n| Arc::new(Mutex::new(Total(n))));
+        let dead = Arc::new(Mutex::new(Total(0)));
+        observable.register_concrete(&totals[0]);
+        observable.register_concrete(&dead);
+        observable.register_concrete(&totals[1]);
+        drop(dead);
+
+        let notified = observable.send_to_all_returning(&10);
+        assert_eq!(notified.len(), 2);
+        let seen: Vec<u32> = notified
+            .iter()
+            // every lock has been released, so none of these blocks
+            .map(|o| o.try_lock().unwrap().as_any().and_then(|a| a.downcast_ref::<Total>()).unwrap().0)
+            .collect();
+        assert_eq!(seen, vec![13, 15]);
+        assert_eq!(observable.stats().total_delivered, 2);
+    }
+
+    #[test]
+    fn test_send_collect_replies() {
+        use std::sync::mpsc;
+
+        // replies with its own price, at once or, once released, from another thread
+        struct Quote {
+            price: u32,
+            later: Option<mpsc::Receiver<()>>,
+        }
+
+        impl Observer<&'static str> for Quote {
+            fn notify(&mut self, _: &&'static str) {}
+            fn notify_with_reply(&mut self, _: &&'static str, reply: &Reply) {
+                let price = self.price;
+                match self.later.take() {
+                    None => {
+                        reply.send(price);
+                    }
+                    Some(release) => {
+                        let reply = reply.clone();
+                        std::thread::spawn(move || {
+                            let _ = release.recv();
+                            reply.send(price);
+                        });
+                    }
+                }
+            }
+        }
+
+        let (release, released) = mpsc::channel();
+        let mut observable = Observable::new();
+        let now = Arc::new(Mutex::new(Quote { price: 3, later: None }));
+        let later = Arc::new(Mutex::new(Quote { price: 5, later: Some(released) }));
+        let (_silent, silent) = test_util::recorder();
+        observable.register_concrete(&later);
+        observable.register_concrete(&now);
+        observable.register(silent);
+
+        release.send(()).unwrap();
+        let mut prices = observable.send_collect_replies::<u32>(&"apple", Duration::from_secs(60));
+        prices.sort();
+        assert_eq!(prices, vec![3, 5]);
+        // answers of another type are dropped
+        assert_eq!(observable.send_collect_replies::<String>(&"pear", Duration::from_secs(60)), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_clone_and_shared_handle() {
+        use crate::test_util::{received, recorder};
+
+        let mut observable = Observable::<u32>::new();
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        let first = observable.register(weak1);
+        observable.register_with_priority(weak2, 1);
+
+        let mut copy = observable.clone();
+        assert_eq!(observable.send_to_all(&1), 2);
+        assert_eq!(copy.send_to_all(&2), 2);
+        assert_eq!(received(&ob1), vec![1, 2]);
+        assert_eq!(received(&ob2), vec![1, 2]);
+
+        // the lists diverge from here
+        let (ob3, weak3) = recorder();
+        copy.register(weak3);
+        // the original's handle means nothing to the copy
+        assert!(!copy.unregister(first));
+        assert_eq!(observable.send_to_all(&3), 2);
+        assert_eq!(copy.send_to_all(&4), 3);
+        assert_eq!(received(&ob1), vec![1, 2, 3, 4]);
+        assert_eq!(received(&ob3), vec![4]);
+
+        let shared = copy.shared_handle();
+        let other = shared.clone();
+        let (ob4, weak4) = recorder();
+        other.lock().unwrap().register(weak4);
+        assert_eq!(shared.lock().unwrap().send_to_all(&5), 4);
+        assert_eq!(received(&ob4), vec![5]);
+    }
+
+    #[test]
+    fn test_stale_sends_counted_on_every_path() {
+        use crate::test_util::recorder;
+
+        let mut observable = Observable::<u32>::new();
+        let (live, live_weak) = recorder();
+        let (dead, dead_weak) = recorder();
+        observable.register(live_weak);
+        observable.register(dead_weak);
+        drop(dead);
+
+        observable.send_batch(&[1, 2]);
+        observable.send_to_all_timeout(&3, Duration::from_secs(60));
+        observable.send_filtered_by(&4, |_| true);
+        observable.send_to_all_unique(&5);
+        observable.send_to_all_parallel(&6);
+        observable.send_to_all_detached(Arc::new(7)).wait();
+        observable.prune();
+        assert_eq!(observable.last_prune_stats(), PruneStats { pruned: 1, max_stale_sends: 6 });
+        drop(live);
+    }
+
+    #[test]
+    fn test_unsized_extensions() {
+        use crate::test_util::{received, recorder};
+
+        struct Len(usize);
+
+        impl Observer<str> for Len {
+            fn notify(&mut self, event: &str) {
+                self.0 += event.len();
+            }
         }
+
+        let a = Arc::new(Mutex::new(Observable::<str>::new()));
+        let b = Arc::new(Mutex::new(Observable::<str>::new()));
+        let (lengths, lengths_weak) = recorder();
+        let stage = b.lock().unwrap().map(|w: &str| w.len());
+        stage.lock().unwrap().observable_mut().register(lengths_weak);
+        let mut a_locked = a.lock().unwrap();
+        a_locked.set_order(Box::new(order::Reverse));
+        let len = Arc::new(Mutex::new(Len(0)));
+        a_locked.register_concrete(&len);
+        assert!(a_locked.chain_to(Arc::downgrade(&b)).unwrap().is_some());
+        assert_eq!(a_locked.snapshot().subscribers.len(), 2);
+        assert!(a_locked.export_config().subscribers.is_empty());
+
+        assert_eq!(a_locked.send_to_all("abc"), 2);
+        assert_eq!(len.lock().unwrap().0, 3);
+        assert_eq!(received(&lengths), vec![3]);
+        drop(a_locked);
+
+        let built = Observable::<str>::builder().capacity(1).build();
+        let reduced = Observable::<str>::with_reducer(0, |total: &mut usize, w: &str| *total += w.len());
+        reduced.send_to_all("de");
+        assert_eq!(*reduced.state(), 2);
+        assert!(built.is_empty());
     }
 
     #[test]
@@ -0,0 +1,104 @@
+use std::{cell::RefCell, rc::Weak};
+
+use crate::Observer;
+
+/// A single-threaded counterpart of [`Observable`](crate::Observable): subscribers are
+/// held as `rc::Weak<RefCell<_>>`, so a send borrows each observer in turn instead of
+/// locking a mutex.
+///
+/// Being built on `Rc`, a `LocalObservable` is neither `Send` nor `Sync` and stays on the
+/// thread that created it:
+///
+/// ```compile_fail
+/// fn assert_send<S: Send>() {}
+/// assert_send::<rust_exercise_2_event::LocalObservable<u32>>();
+/// ```
+pub struct LocalObservable<T> {
+    // indexed by the value `register` returned; `None` marks an unregistered slot
+    slots: Vec<Option<Weak<RefCell<dyn Observer<T>>>>>,
+}
+
+impl<T> LocalObservable<T> {
+    pub fn new() -> LocalObservable<T> {
+        LocalObservable { slots: Vec::new() }
+    }
+    /// Registers `observer` and returns the index to [`unregister`](Self::unregister) it
+    /// with. Indices are not reused.
+    pub fn register(&mut self, observer: Weak<RefCell<dyn Observer<T>>>) -> usize {
+        self.slots.push(Some(observer));
+        self.slots.len() - 1
+    }
+    /// Removes the subscriber at `i`, returning whether it was registered.
+    pub fn unregister(&mut self, i: usize) -> bool {
+        self.slots.get_mut(i).and_then(Option::take).is_some()
+    }
+    /// Number of registered subscribers, dead or alive.
+    pub fn len(&self) -> usize {
+        self.slots.iter().flatten().count()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Number of subscribers that are still alive.
+    pub fn live_count(&self) -> usize {
+        self.slots.iter().flatten().filter(|o| o.strong_count() > 0).count()
+    }
+    /// Unregisters the subscribers that have been dropped, returning how many.
+    pub fn prune(&mut self) -> usize {
+        let dead = self.slots.iter_mut().filter(|o| o.as_ref().is_some_and(|o| o.strong_count() == 0));
+        dead.map(Option::take).count()
+    }
+    /// Sends `message` to every live subscriber in registration order, returning how many
+    /// were notified. An observer that is already borrowed, for instance because this is a
+    /// nested send from its own `notify`, is skipped.
+    pub fn send_to_all(&self, message: &T) -> usize {
+        self.slots.iter()
+            .flatten()
+            .filter_map(Weak::upgrade)
+            .filter(|o| o.try_borrow_mut().map(|mut o| o.notify(message)).is_ok())
+            .count()
+    }
+}
+
+impl<T> Default for LocalObservable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::local::*;
+    use std::rc::Rc;
+
+    #[derive(Default)]
+    struct Sum(u32);
+
+    impl Observer<u32> for Sum {
+        fn notify(&mut self, event: &u32) {
+            self.0 += event;
+        }
+    }
+
+    #[test]
+    fn test_local_observable() {
+        let ob1 = Rc::new(RefCell::new(Sum::default()));
+        let ob2 = Rc::new(RefCell::new(Sum::default()));
+        let mut observable = LocalObservable::new();
+        let weak1: Weak<RefCell<Sum>> = Rc::downgrade(&ob1);
+        let weak2: Weak<RefCell<Sum>> = Rc::downgrade(&ob2);
+        observable.register(weak1);
+        let second = observable.register(weak2);
+
+        assert_eq!(observable.send_to_all(&2), 2);
+        assert!(observable.unregister(second));
+        assert_eq!(observable.send_to_all(&3), 1);
+        assert_eq!((ob1.borrow().0, ob2.borrow().0), (5, 2));
+
+        drop(ob1);
+        assert_eq!(observable.send_to_all(&4), 0);
+        assert_eq!(observable.prune(), 1);
+        assert!(observable.is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use std::{io::{self, Write}, mem, sync::{Mutex, PoisonError}};
+
+use crate::Observable;
+
+type Record<T> = Box<dyn FnMut(&T) -> io::Result<()> + Send>;
+
+// Writes one record per dispatched message; failures are kept rather than raised so
+// that a broken log never stops delivery.
+pub(crate) struct Logger<T: ?Sized> {
+    record: Mutex<Record<T>>,
+    errors: Mutex<Vec<io::Error>>,
+}
+
+impl<T: ?Sized> Logger<T> {
+    pub(crate) fn log(&self, message: &T) {
+        let result = (self.record.lock().unwrap_or_else(PoisonError::into_inner))(message);
+        if let Err(e) = result {
+            self.errors.lock().unwrap_or_else(PoisonError::into_inner).push(e);
+        }
+    }
+}
+
+impl<T: ?Sized> Observable<T> {
+    /// Makes [`send_to_all`](Self::send_to_all) and each of its `send_to_all_*` variants
+    /// also write a record of the message to `writer`, formatted by `format`, before it is
+    /// delivered. Nested sends that are ignored leave no record, and neither do targeted
+    /// sends such as [`send_to`](Self::send_to), [`send_to_topic`](Self::send_to_topic)
+    /// or [`send_batch`](Self::send_batch).
+    ///
+    /// Write and formatting errors never interrupt a send; they are collected and can be
+    /// retrieved with [`take_log_errors`](Self::take_log_errors).
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use rust_exercise_2_event::Observable;
+    ///
+    /// let observable = Observable::<u32>::new()
+    ///     .with_logger(std::io::sink(), |w, n| writeln!(w, "{{\"value\":{}}}", n));
+    /// observable.send_to_all(&1);
+    /// assert!(observable.take_log_errors().is_empty());
+    /// ```
+    pub fn with_logger<W, F>(mut self, mut writer: W, mut format: F) -> Observable<T>
+    where
+        W: Write + Send + 'static,
+        F: FnMut(&mut W, &T) -> io::Result<()> + Send + 'static,
+    {
+        self.logger = Some(Logger {
+            record: Mutex::new(Box::new(move |message| format(&mut writer, message))),
+            errors: Mutex::new(Vec::new()),
+        });
+        self
+    }
+    // Called by every `send_to_all*` variant before it delivers.
+    pub(crate) fn log(&self, message: &T) {
+        if let Some(logger) = &self.logger {
+            logger.log(message);
+        }
+    }
+    /// Returns and clears the errors hit while logging, oldest first.
+    pub fn take_log_errors(&self) -> Vec<io::Error> {
+        self.logger.as_ref()
+            .map(|logger| mem::take(&mut *logger.errors.lock().unwrap_or_else(PoisonError::into_inner)))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::logger::*;
+    use std::sync::Arc;
+
+    // a writer the test can read back after handing it to the observable
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk full"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_with_logger() {
+        let buffer = SharedBuffer::default();
+        let observable = Observable::<(u32, &'static str)>::new()
+            .with_logger(buffer.clone(), |w, (id, name)| writeln!(w, "{{\"id\":{},\"name\":\"{}\"}}", id, name));
+
+        observable.send_to_all(&(1, "a"));
+        observable.send_to_all(&(2, "b"));
+
+        let log = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(log.lines().collect::<Vec<_>>(), vec![r#"{"id":1,"name":"a"}"#, r#"{"id":2,"name":"b"}"#]);
+        assert!(observable.take_log_errors().is_empty());
+    }
+
+    #[test]
+    fn test_logger_covers_send_to_all_variants() {
+        let buffer = SharedBuffer::default();
+        let mut observable = Observable::<u32>::new().with_logger(buffer.clone(), |w, n| writeln!(w, "{}", n));
+
+        observable.send_to_all(&0);
+        observable.send_to_all_ordered(&1);
+        observable.send_to_all_with_ctx(&2, &observable.next_context());
+        observable.send_to_all_budgeted(&3, 1);
+        observable.send_to_all_complete(&4);
+        observable.send_to_all_detailed(&5);
+        observable.send_to_all_isolated(&6);
+        observable.send_to_all_unique(&7);
+        observable.send_to_all_try(&8);
+        observable.send_to_all_parallel(&9);
+        observable.send_to_all_detached(Arc::new(10)).wait();
+        observable.send_to_all_stoppable(&11);
+        observable.send_to_all_timeout(&12, std::time::Duration::ZERO);
+        observable.send_to_all_owned(13);
+        observable.send_to_all_reverse(&14);
+        observable.send_to_all_cancellable(&15, &std::sync::atomic::AtomicBool::new(false));
+        observable.send_to_all_cloned(&16);
+        observable.send_to_all_with(&17, |_, _| {});
+        observable.send_to_all_retry(&18, 0, std::time::Duration::ZERO);
+        observable.send_to_all_deadline(&19, std::time::Instant::now() + std::time::Duration::from_secs(60));
+        observable.send_to_all_tracked(&20);
+        observable.send_to_all_indexed(&21);
+        observable.send_and_prune(&22);
+        observable.send_to_all_returning(&23);
+        observable.send_collect(&24);
+        observable.query_all::<u32>(&25);
+        observable.send_collect_replies::<u32>(&26, std::time::Duration::ZERO);
+        observable.send_if_changed(&27);
+
+        let log = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(log.lines().collect::<Vec<_>>(), (0..28).map(|n| n.to_string()).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_logger_skips_unchanged_values() {
+        let buffer = SharedBuffer::default();
+        let mut observable = Observable::<u32>::new().with_logger(buffer.clone(), |w, n| writeln!(w, "{}", n));
+        let (_observer, weak) = crate::test_util::recorder();
+        let _subscription = observable.register(weak);
+
+        observable.send_if_changed(&1);
+        observable.send_if_changed(&1);
+        observable.send_if_changed(&2);
+
+        let log = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        assert_eq!(log.lines().collect::<Vec<_>>(), vec!["1", "2"]);
+    }
+
+    #[test]
+    fn test_logger_errors() {
+        let observable = Observable::<u32>::new().with_logger(FailingWriter, |w, n| writeln!(w, "{}", n));
+
+        observable.send_to_all(&1);
+        observable.send_to_all(&2);
+
+        let errors = observable.take_log_errors();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].to_string(), "disk full");
+        assert!(observable.take_log_errors().is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+/// Implements [`Observer`](crate::Observer) for a type from a closure-like body.
+///
+/// `observer!(Type: Event, |this, event| body)` expands to
+///
+/// ```ignore
+/// impl Observer<Event> for Type {
+///     fn notify(&mut self, event: &Event) {
+///         let this = self;
+///         body
+///     }
+/// }
+/// ```
+///
+/// `self` cannot be named from inside a macro argument, so the body reaches the
+/// observer through `this` (any identifier works). The event argument is a pattern,
+/// which makes matching a single variant short:
+///
+/// ```
+/// use core::cell::RefCell;
+/// use rust_exercise_2_event::{observer, ArrayObservable};
+///
+/// enum Key {
+///     Down(u8),
+///     Up(u8),
+/// }
+///
+/// #[derive(Default)]
+/// struct Presses(usize);
+///
+/// observer!(Presses: Key, |this, event| {
+///     if let Key::Down(_) = event {
+///         this.0 += 1;
+///     }
+/// });
+///
+/// let presses = RefCell::new(Presses::default());
+/// let mut observable = ArrayObservable::<Key, 1>::new();
+/// observable.register(&presses);
+///
+/// observable.send_to_all(&Key::Down(1));
+/// observable.send_to_all(&Key::Up(1));
+/// assert_eq!(presses.borrow().0, 1);
+/// ```
+#[macro_export]
+macro_rules! observer {
+    ($ty:ty : $event:ty, |$this:ident, $arg:pat_param| $body:expr) => {
+        impl $crate::Observer<$event> for $ty {
+            fn notify(&mut self, event: &$event) {
+                let $this = self;
+                let $arg = event;
+                $body
+            }
+        }
+    };
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+
+    use crate::Observable;
+    use std::sync::{Arc, Mutex};
+
+    enum MyMessage {
+        Msg(&'static str),
+        Other,
+    }
+
+    // implemented through the macro under test, so not the shared `Recorder` fixture
+    #[derive(Default)]
+    struct Texts {
+        received: Vec<&'static str>,
+    }
+
+    observer!(Texts: MyMessage, |this, event| {
+        if let MyMessage::Msg(text) = event {
+            this.received.push(text);
+        }
+    });
+
+    #[test]
+    fn test_observer_macro() {
+        let mut observable = Observable::<MyMessage>::new();
+
+        let ob1 = Arc::new(Mutex::new(Texts::default()));
+        observable.register_concrete(&ob1);
+
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("a")), 1);
+        assert_eq!(observable.send_to_all(&MyMessage::Other), 1);
+        assert_eq!(observable.send_to_all(&MyMessage::Msg("b")), 1);
+        assert_eq!(ob1.lock().ok().map(|v| v.received.clone()), Some(vec!["a", "b"]));
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+use crate::{Observable, Observer};
+
+/// Observer of `T` that converts each event with `f` and forwards the result to its own
+/// [`Observable<U>`].
+///
+/// The conversion runs once per event, however many observers are attached downstream.
+pub struct MapObserver<U, F> {
+    f: F,
+    observable: Observable<U>,
+}
+
+impl<U, F> MapObserver<U, F> {
+    pub fn new(f: F) -> MapObserver<U, F> {
+        MapObserver {
+            f,
+            observable: Observable::new(),
+        }
+    }
+    /// The downstream observable that receives converted events.
+    pub fn observable(&self) -> &Observable<U> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<U> {
+        &mut self.observable
+    }
+}
+
+impl<T: ?Sized, U, F> Observer<T> for MapObserver<U, F>
+where
+    F: Fn(&T) -> U + Send,
+{
+    fn notify(&mut self, event: &T) {
+        self.observable.send_to_all(&(self.f)(event));
+    }
+}
+
+// Stage behind `map_filter`: sends what `f` keeps on to `target`.
+struct MapFilter<U, F> {
+    f: F,
+    target: Arc<Mutex<Observable<U>>>,
+}
+
+impl<T: ?Sized, U, F> Observer<T> for MapFilter<U, F>
+where
+    F: Fn(&T) -> Option<U> + Send,
+{
+    fn notify(&mut self, event: &T) {
+        let Some(mapped) = (self.f)(event) else {
+            return;
+        };
+        if let Ok(target) = self.target.lock() {
+            target.send_to_all(&mapped);
+        }
+    }
+}
+
+/// Observer of `T` that converts each event into a `U` through `U`'s [`From<&T>`] impl
+/// and forwards it to its own [`Observable<U>`]: a [`MapObserver`] whose conversion is
+/// the standard trait rather than a closure.
+pub struct ConvertingForwarder<T: ?Sized, U> {
+    observable: Observable<U>,
+    _source: PhantomData<fn(&T)>,
+}
+
+impl<T: ?Sized, U> ConvertingForwarder<T, U> {
+    pub fn new() -> ConvertingForwarder<T, U> {
+        ConvertingForwarder {
+            observable: Observable::new(),
+            _source: PhantomData,
+        }
+    }
+    /// The downstream observable that receives converted events.
+    pub fn observable(&self) -> &Observable<U> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<U> {
+        &mut self.observable
+    }
+}
+
+impl<T: ?Sized, U> Default for ConvertingForwarder<T, U> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized, U> Observer<T> for ConvertingForwarder<T, U>
+where
+    U: for<'a> From<&'a T>,
+{
+    fn notify(&mut self, event: &T) {
+        self.observable.send_to_all(&U::from(event));
+    }
+}
+
+impl<T: ?Sized + 'static> Observable<T> {
+    /// Registers a [`ConvertingForwarder`] converting this observable's events into `U`.
+    ///
+    /// Like [`map`](Self::map), only a weak reference is registered: the stage stays
+    /// attached for as long as the returned `Arc` is kept.
+    pub fn map_into<U>(&mut self) -> Arc<Mutex<ConvertingForwarder<T, U>>>
+    where
+        U: for<'a> From<&'a T> + 'static,
+    {
+        let stage = Arc::new(Mutex::new(ConvertingForwarder::new()));
+        self.register_concrete(&stage);
+        stage
+    }
+    /// Derives a new observable from this one: each event is passed to `f`, and the
+    /// values it returns as `Some` are sent to all subscribers of the returned
+    /// observable. `None` drops the event.
+    ///
+    /// Unlike [`map`](Self::map), this observable owns the stage, and the stage holds the
+    /// derived observable, so a pipeline built from it stays connected for as long as its
+    /// source lives, whichever intermediate handles are dropped. Sending locks the derived
+    /// observable, so that mutex must not be held by whoever sends here.
+    pub fn map_filter<U, F>(&mut self, f: F) -> Arc<Mutex<Observable<U>>>
+    where
+        U: 'static,
+        F: Fn(&T) -> Option<U> + Send + 'static,
+    {
+        let target = Arc::new(Mutex::new(Observable::new()));
+        self.register_owned(Arc::new(Mutex::new(MapFilter { f, target: target.clone() })));
+        target
+    }
+}
+
+impl<T: ?Sized> Observable<T> {
+    /// Registers a [`MapObserver`] converting this observable's events with `f`.
+    ///
+    /// Only a weak reference is registered: the stage stays attached for as long as
+    /// the returned `Arc` is kept.
+    pub fn map<U, F>(&mut self, f: F) -> Arc<Mutex<MapObserver<U, F>>>
+    where
+        U: 'static,
+        F: Fn(&T) -> U + Send + 'static,
+    {
+        let stage = Arc::new(Mutex::new(MapObserver::new(f)));
+        let stage_dyn: Arc<Mutex<dyn Observer<T>>> = stage.clone();
+        self.register(Arc::downgrade(&stage_dyn));
+        stage
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::map::*;
+    use crate::test_util::{received, recorder};
+
+    #[test]
+    fn test_map_chain() {
+        let mut observable = Observable::<usize>::new();
+
+        let doubled = observable.map(|n: &usize| n * 2);
+        let formatted = doubled.lock().unwrap().observable_mut().map(|n: &usize| format!("#{}", n));
+
+        let (ob1, ob1w) = recorder();
+        formatted.lock().unwrap().observable_mut().register(ob1w);
+
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(observable.send_to_all(&2), 1);
+        assert_eq!(received(&ob1), vec!["#2".to_string(), "#4".to_string()]);
+
+        drop(doubled);
+        assert_eq!(observable.send_to_all(&3), 0);
+    }
+
+    // `String: From<&i32>` is not in std, and the orphan rule keeps it out of here too
+    #[derive(Debug, Clone, PartialEq)]
+    struct Label(String);
+
+    impl From<&i32> for Label {
+        fn from(n: &i32) -> Label {
+            Label(format!("n={}", n))
+        }
+    }
+
+    #[test]
+    fn test_map_filter_chain() {
+        let mut observable = Observable::<i32>::new();
+        let even = observable.map_filter(|n: &i32| (n % 2 == 0).then_some(*n));
+        let labels = even.lock().unwrap().map_filter(|n: &i32| Some(format!("#{}", n)));
+        drop(even);
+
+        let (ob1, ob1w) = recorder();
+        labels.lock().unwrap().register(ob1w);
+        for n in 1..=5 {
+            observable.send_to_all(&n);
+        }
+        assert_eq!(received(&ob1), vec!["#2".to_string(), "#4".to_string()]);
+
+        // unsized sources too: the lengths of the non-empty strings
+        let mut words = Observable::<str>::new();
+        let lengths = words.map_filter(|w: &str| (!w.is_empty()).then_some(w.len()));
+        let (ob2, ob2w) = recorder();
+        lengths.lock().unwrap().register(ob2w);
+        for w in ["abc", "", "de"] {
+            words.send_to_all(w);
+        }
+        assert_eq!(received(&ob2), vec![3, 2]);
+    }
+
+    #[test]
+    fn test_map_into() {
+        let mut observable = Observable::<i32>::new();
+        let labels = observable.map_into::<Label>();
+        let (ob1, ob1w) = recorder();
+        labels.lock().unwrap().observable_mut().register(ob1w);
+
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(observable.send_to_all(&-2), 1);
+        assert_eq!(received(&ob1), vec![Label("n=1".into()), Label("n=-2".into())]);
+
+        // unsized sources work too, through std's `String: From<&str>`
+        let mut names = Observable::<str>::new();
+        let owned = names.map_into::<String>();
+        let (ob2, ob2w) = recorder();
+        owned.lock().unwrap().observable_mut().register(ob2w);
+        names.send_to_all("ada");
+        assert_eq!(received(&ob2), vec!["ada".to_string()]);
+
+        drop(labels);
+        assert_eq!(observable.send_to_all(&3), 0);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{DeliveryResult, Observable};
+
+#[cfg(feature = "metrics")]
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "metrics")]
+/// Per-delivery latencies of an observable's sends, returned by
+/// [`Observable::latency_report`]: for each subscriber notified, the time from the start
+/// of the send to the end of its `notify`. Only compiled with the `metrics` feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LatencyReport {
+    /// Deliveries measured.
+    pub deliveries: u64,
+    /// Zero when nothing was measured, like `max` and `mean`.
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    /// Deliveries by latency: `buckets[i]` counts those up to
+    /// [`BUCKET_BOUNDS[i]`](Self::BUCKET_BOUNDS) and above the previous bound, the last
+    /// one those above one second.
+    pub buckets: [u64; 8],
+}
+
+#[cfg(feature = "metrics")]
+impl LatencyReport {
+    /// Inclusive upper bounds of all but the last bucket, one decade apart.
+    pub const BUCKET_BOUNDS: [Duration; 7] = [
+        Duration::from_micros(1),
+        Duration::from_micros(10),
+        Duration::from_micros(100),
+        Duration::from_millis(1),
+        Duration::from_millis(10),
+        Duration::from_millis(100),
+        Duration::from_secs(1),
+    ];
+}
+
+// Accumulated with atomics, since sends only borrow the observable.
+#[cfg(feature = "metrics")]
+pub(crate) struct Latency {
+    deliveries: AtomicU64,
+    // nanoseconds, saturating
+    total: AtomicU64,
+    min: AtomicU64,
+    max: AtomicU64,
+    buckets: [AtomicU64; 8],
+    // `Instant::now`, unless a test stands in its own clock
+    clock: fn() -> Instant,
+}
+
+#[cfg(feature = "metrics")]
+impl Latency {
+    pub(crate) fn new() -> Latency {
+        Latency {
+            deliveries: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+            min: AtomicU64::new(u64::MAX),
+            max: AtomicU64::new(0),
+            buckets: Default::default(),
+            clock: Instant::now,
+        }
+    }
+    fn record(&self, latency: Duration) {
+        let nanos = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.deliveries.fetch_add(1, Ordering::Relaxed);
+        // `fetch_update` only fails when the closure returns `None`, which it never does
+        let _ = self.total.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |t| Some(t.saturating_add(nanos)));
+        self.min.fetch_min(nanos, Ordering::Relaxed);
+        self.max.fetch_max(nanos, Ordering::Relaxed);
+        let bucket = LatencyReport::BUCKET_BOUNDS.partition_point(|&bound| bound < latency);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+    fn report(&self) -> LatencyReport {
+        let deliveries = self.deliveries.load(Ordering::Relaxed);
+        if deliveries == 0 {
+            return LatencyReport::default();
+        }
+        LatencyReport {
+            deliveries,
+            min: Duration::from_nanos(self.min.load(Ordering::Relaxed)),
+            max: Duration::from_nanos(self.max.load(Ordering::Relaxed)),
+            mean: Duration::from_nanos(self.total.load(Ordering::Relaxed) / deliveries),
+            buckets: self.buckets.each_ref().map(|b| b.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+// When the send being measured started.
+#[cfg(feature = "metrics")]
+pub(crate) type LatencyStart = Instant;
+// a unit struct rather than `()`, which the sends could not bind without a lint
+#[cfg(not(feature = "metrics"))]
+pub(crate) struct LatencyStart;
+
+#[cfg(feature = "metrics")]
+impl<T: ?Sized> Observable<T> {
+    /// Latencies of every delivery made by [`send_to_all`](Self::send_to_all), including
+    /// its detailed and `try_` forms, and by [`send_collect`](Self::send_collect), [`query_all`](Self::query_all) and
+    /// [`send_collect_replies`](Self::send_collect_replies), since
+    /// the observable was created. Other sends are not measured. Only compiled with the `metrics` feature; without it nothing is timed.
+    pub fn latency_report(&self) -> LatencyReport {
+        self.latency.report()
+    }
+    pub(crate) fn latency_start(&self) -> LatencyStart {
+        (self.latency.clock)()
+    }
+    pub(crate) fn latency_delivery(&self, start: &LatencyStart, result: DeliveryResult) {
+        if result == DeliveryResult::Delivered {
+            self.latency.record((self.latency.clock)().saturating_duration_since(*start));
+        }
+    }
+}
+
+// Without the feature the hooks are empty and inline away, as the tracing ones do.
+#[cfg(not(feature = "metrics"))]
+impl<T: ?Sized> Observable<T> {
+    #[inline(always)]
+    pub(crate) fn latency_start(&self) -> LatencyStart {
+        LatencyStart
+    }
+    #[inline(always)]
+    pub(crate) fn latency_delivery(&self, _start: &LatencyStart, _result: DeliveryResult) {}
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+
+    use crate::metrics::*;
+    use crate::Observer;
+    use std::cell::Cell;
+    use std::sync::{Arc, Mutex};
+
+    thread_local! {
+        // how far the fake clock has been moved past `EPOCH`
+        static ELAPSED: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+        static EPOCH: Instant = Instant::now();
+    }
+
+    fn fake_now() -> Instant {
+        EPOCH.with(|epoch| *epoch + ELAPSED.get())
+    }
+
+    // takes `.0` of the fake clock's time to handle an event
+    struct Slow(Duration);
+
+    impl Observer<u8> for Slow {
+        fn notify(&mut self, _: &u8) {
+            ELAPSED.set(ELAPSED.get() + self.0);
+        }
+    }
+
+    #[test]
+    fn test_latency_report() {
+        let mut observable = Observable::new();
+        observable.latency.clock = fake_now;
+        assert_eq!(observable.latency_report(), LatencyReport::default());
+
+        let fast = Arc::new(Mutex::new(Slow(Duration::ZERO)));
+        let slow = Arc::new(Mutex::new(Slow(Duration::from_millis(20))));
+        observable.register_concrete(&fast);
+        observable.register_concrete(&slow);
+        observable.send_to_all(&1);
+        observable.send_to_all(&2);
+
+        let report = observable.latency_report();
+        assert_eq!(report.deliveries, 4);
+        assert_eq!(report.min, Duration::ZERO);
+        assert_eq!(report.max, Duration::from_millis(20));
+        assert_eq!(report.mean, Duration::from_millis(10));
+        assert_eq!(report.buckets, [2, 0, 0, 0, 0, 2, 0, 0]);
+    }
+}
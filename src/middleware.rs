@@ -0,0 +1,99 @@
+use std::sync::PoisonError;
+
+use crate::{DispatchGuard, Observable};
+
+/// A stage that sees every message sent with [`Observable::send_to_all_owned`] before
+/// any observer does, and may replace it or drop it.
+///
+/// Stages run in the order they were added; the first one returning `None` ends the
+/// chain and the message is not delivered at all.
+pub trait Middleware<T: ?Sized>: Send {
+    fn process(&mut self, event: T) -> Option<T>
+    where
+        T: Sized;
+}
+
+impl<T: ?Sized> Observable<T> {
+    /// Appends `middleware` to the chain run by [`send_to_all_owned`](Self::send_to_all_owned).
+    pub fn add_middleware<M: Middleware<T> + 'static>(&mut self, middleware: M) {
+        self.middleware.get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Box::new(middleware));
+    }
+    /// Passes `message` through the middleware chain and sends what comes out to every
+    /// live subscriber, returning how many were notified; 0 if a stage dropped it.
+    ///
+    /// Middleware takes messages by value, so it only runs here: [`send_to_all`] and the
+    /// other sends taking `&T` deliver without it. Nested sends are ignored as with
+    /// [`send_to_all`].
+    ///
+    /// [`send_to_all`]: Self::send_to_all
+    pub fn send_to_all_owned(&self, message: T) -> usize
+    where
+        T: Sized,
+    {
+        let Ok(_guard) = DispatchGuard::enter(self) else {
+            return 0;
+        };
+        let message = self.middleware.lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter_mut()
+            .try_fold(message, |message, stage| stage.process(message));
+        let Some(message) = message else {
+            return 0;
+        };
+        self.log(&message);
+        self.record(1, self.recipients().map(|(_, s)| s.deliver(&message, self.poison_policy)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::middleware::*;
+    use crate::test_util::{received, recorder};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Input {
+        Key(char),
+        Heartbeat,
+    }
+
+    struct DropHeartbeats;
+
+    impl Middleware<Input> for DropHeartbeats {
+        fn process(&mut self, event: Input) -> Option<Input> {
+            match event {
+                Input::Heartbeat => None,
+                event => Some(event),
+            }
+        }
+    }
+
+    struct Uppercase;
+
+    impl Middleware<Input> for Uppercase {
+        fn process(&mut self, event: Input) -> Option<Input> {
+            match event {
+                Input::Key(c) => Some(Input::Key(c.to_ascii_uppercase())),
+                event => Some(event),
+            }
+        }
+    }
+
+    #[test]
+    fn test_middleware() {
+        let mut observable = Observable::<Input>::new();
+        observable.add_middleware(DropHeartbeats);
+        observable.add_middleware(Uppercase);
+        let (ob1, ob1w) = recorder();
+        observable.register(ob1w);
+
+        assert_eq!(observable.send_to_all_owned(Input::Key('a')), 1);
+        assert_eq!(observable.send_to_all_owned(Input::Heartbeat), 0);
+        assert_eq!(observable.send_to_all(&Input::Heartbeat), 1);
+
+        assert_eq!(received(&ob1), vec![Input::Key('A'), Input::Heartbeat]);
+        assert_eq!(observable.stats().total_sent, 2);
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+use crate::Observable;
+
+/// Decides the order in which [`Observable::send_to_all`] visits the subscribers,
+/// installed with [`Observable::set_order`].
+///
+/// Sends take `&self`, so a strategy that changes from one send to the next keeps its
+/// state in atomics or a lock, as the built-in [`RoundRobin`] and [`Random`] do.
+pub trait DeliveryOrder: Send + Sync {
+    /// The order in which to visit `count` subscribers, as positions in their usual
+    /// delivery order (by position, priority, then registration). Positions left out are
+    /// not notified by this send, and positions from `count` on are ignored.
+    fn order(&self, count: usize) -> Vec<usize>;
+}
+
+/// The usual delivery order, unchanged.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sequential;
+
+impl DeliveryOrder for Sequential {
+    fn order(&self, count: usize) -> Vec<usize> {
+        (0..count).collect()
+    }
+}
+
+/// The usual delivery order, backwards.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Reverse;
+
+impl DeliveryOrder for Reverse {
+    fn order(&self, count: usize) -> Vec<usize> {
+        (0..count).rev().collect()
+    }
+}
+
+/// Starts each send one subscriber later than the previous one, wrapping around, so that
+/// every subscriber takes its turn at being notified first.
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    start: AtomicUsize,
+}
+
+impl RoundRobin {
+    pub fn new() -> RoundRobin {
+        RoundRobin::default()
+    }
+}
+
+impl DeliveryOrder for RoundRobin {
+    fn order(&self, count: usize) -> Vec<usize> {
+        if count == 0 {
+            return Vec::new();
+        }
+        let start = self.start.fetch_add(1, Ordering::Relaxed) % count;
+        (start..count).chain(0..start).collect()
+    }
+}
+
+/// Shuffles the subscribers anew for every send.
+///
+/// The shuffle only has to spread the cost of going first, not resist prediction, so a
+/// xorshift generator seeded from std's hasher keys stands in for a `rand` dependency.
+#[derive(Debug)]
+pub struct Random {
+    state: AtomicU64,
+}
+
+impl Random {
+    /// Seeded differently on every call.
+    pub fn new() -> Random {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_usize(0);
+        Random::with_seed(hasher.finish())
+    }
+    /// Seeded with `seed`, for a reproducible sequence of shuffles.
+    pub fn with_seed(seed: u64) -> Random {
+        // xorshift never leaves 0
+        Random { state: AtomicU64::new(seed.max(1)) }
+    }
+    fn next(&self) -> u64 {
+        let step = |mut x: u64| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x
+        };
+        let previous = self.state.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |x| Some(step(x)));
+        step(previous.unwrap_or_else(|x| x))
+    }
+    // Uniform in [0, 1), from the top 53 bits.
+    pub(crate) fn next_f64(&self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Default for Random {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeliveryOrder for Random {
+    fn order(&self, count: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..count).collect();
+        // Fisher-Yates; the modulo bias is irrelevant at subscriber counts
+        for i in (1..count).rev() {
+            order.swap(i, (self.next() % (i as u64 + 1)) as usize);
+        }
+        order
+    }
+}
+
+impl<T: ?Sized> Observable<T> {
+    /// Makes [`send_to_all`](Self::send_to_all), along with the other sends built on it
+    /// ([`try_send_to_all`](Self::try_send_to_all) and the detailed forms), visit the
+    /// subscribers in the order `order` gives, asked afresh on every send. The other sends
+    /// keep the usual order. [`Sequential`] restores the default.
+    pub fn set_order(&mut self, order: Box<dyn DeliveryOrder>) {
+        self.delivery_order = Some(order);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::order::*;
+    use crate::test_util::{received, recorder};
+    use std::sync::atomic::AtomicBool;
+    use std::sync::{Arc, Mutex};
+
+    // forwards on one send, backwards on the next
+    #[derive(Default)]
+    struct Alternate {
+        backwards: AtomicBool,
+    }
+
+    impl DeliveryOrder for Alternate {
+        fn order(&self, count: usize) -> Vec<usize> {
+            if self.backwards.fetch_xor(true, Ordering::Relaxed) {
+                (0..count).rev().collect()
+            } else {
+                (0..count).collect()
+            }
+        }
+    }
+
+    // the tags of the subscribers notified, in the order they were
+    type Visits = Arc<Mutex<Vec<usize>>>;
+
+    struct Tagged(usize, Visits);
+
+    impl crate::Observer<u8> for Tagged {
+        fn notify(&mut self, _: &u8) {
+            self.1.lock().unwrap().push(self.0);
+        }
+    }
+
+    fn tagged(observable: &mut Observable<u8>, n: usize) -> (Vec<Arc<Mutex<Tagged>>>, Visits) {
+        let visits = Arc::new(Mutex::new(Vec::new()));
+        let observers: Vec<_> = (0..n).map(|i| Arc::new(Mutex::new(Tagged(i, visits.clone())))).collect();
+        for observer in &observers {
+            observable.register_concrete(observer);
+        }
+        (observers, visits)
+    }
+
+    #[test]
+    fn test_custom_order() {
+        let mut observable = Observable::new();
+        observable.set_order(Box::new(Alternate::default()));
+        let (_observers, visits) = tagged(&mut observable, 3);
+
+        for _ in 0..3 {
+            assert_eq!(observable.send_to_all(&0), 3);
+        }
+        assert_eq!(*visits.lock().unwrap(), vec![0, 1, 2, 2, 1, 0, 0, 1, 2]);
+    }
+
+    #[test]
+    fn test_builtin_orders() {
+        let mut observable = Observable::new();
+        let (_observers, visits) = tagged(&mut observable, 3);
+        let take = || std::mem::take(&mut *visits.lock().unwrap());
+
+        observable.set_order(Box::new(Reverse));
+        observable.send_to_all(&0);
+        assert_eq!(take(), vec![2, 1, 0]);
+
+        observable.set_order(Box::new(RoundRobin::new()));
+        observable.send_to_all(&0);
+        observable.send_to_all(&0);
+        assert_eq!(take(), vec![0, 1, 2, 1, 2, 0]);
+
+        observable.set_order(Box::new(Random::with_seed(7)));
+        observable.send_to_all(&0);
+        let mut shuffled = take();
+        shuffled.sort();
+        assert_eq!(shuffled, vec![0, 1, 2]);
+
+        observable.set_order(Box::new(Sequential));
+        observable.send_to_all(&0);
+        assert_eq!(take(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_order_skips_left_out() {
+        struct FirstOnly;
+
+        impl DeliveryOrder for FirstOnly {
+            fn order(&self, _count: usize) -> Vec<usize> {
+                vec![0, 5]
+            }
+        }
+
+        let mut observable = Observable::new();
+        observable.set_order(Box::new(FirstOnly));
+        let (ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+
+        assert_eq!(observable.send_to_all(&1u8), 1);
+        assert_eq!((received(&ob1), received(&ob2)), (vec![1], vec![]));
+    }
+}
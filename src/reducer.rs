@@ -0,0 +1,117 @@
+use std::sync::{Mutex, MutexGuard, PoisonError, Weak};
+
+use crate::{DispatchGuard, Observable, Observer, Subscription};
+
+type Reducer<S, T> = Box<dyn FnMut(&mut S, &T) + Send>;
+
+/// An [`Observable`] that also folds every message it sends into a state of its own, for
+/// event sourcing: the state is what the events add up to, and observers keep hearing
+/// the events themselves.
+///
+/// ```
+/// use rust_exercise_2_event::Observable;
+///
+/// enum Account {
+///     Deposit(u32),
+///     Withdraw(u32),
+/// }
+///
+/// let account = Observable::with_reducer(0, |balance: &mut u32, event: &Account| match event {
+///     Account::Deposit(amount) => *balance += amount,
+///     Account::Withdraw(amount) => *balance -= amount,
+/// });
+/// account.send_to_all(&Account::Deposit(10));
+/// account.send_to_all(&Account::Withdraw(3));
+/// assert_eq!(*account.state(), 7);
+/// ```
+pub struct ReducingObservable<T: ?Sized, S> {
+    observable: Observable<T>,
+    // both behind locks so that sends can reduce through `&self`
+    state: Mutex<S>,
+    reducer: Mutex<Reducer<S, T>>,
+}
+
+impl<T: ?Sized> Observable<T> {
+    /// Creates a [`ReducingObservable`] starting from `initial` and applying `f` to it for
+    /// every message sent.
+    pub fn with_reducer<S, F>(initial: S, f: F) -> ReducingObservable<T, S>
+    where
+        F: FnMut(&mut S, &T) + Send + 'static,
+    {
+        ReducingObservable {
+            observable: Observable::new(),
+            state: Mutex::new(initial),
+            reducer: Mutex::new(Box::new(f)),
+        }
+    }
+}
+
+impl<T: ?Sized, S> ReducingObservable<T, S> {
+    pub fn register(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.observable.register(observer)
+    }
+    /// Applies the reducer to `message`, then sends it to every subscriber, returning how
+    /// many were notified. Observers therefore already find the event in the state.
+    ///
+    /// A nested send is ignored as with [`Observable::send_to_all`] and leaves the state
+    /// alone, so the state only ever reflects messages that went out.
+    pub fn send_to_all(&self, message: &T) -> usize {
+        let Ok(guard) = DispatchGuard::enter(&self.observable) else {
+            return 0;
+        };
+        let mut reducer = self.reducer.lock().unwrap_or_else(PoisonError::into_inner);
+        reducer(&mut self.state(), message);
+        drop((reducer, guard));
+        self.observable.send_to_all(message)
+    }
+    /// The state reduced from every message sent so far. Sends wait while the guard is
+    /// held, so it must not be kept across a send on the same thread.
+    pub fn state(&self) -> MutexGuard<'_, S> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    pub fn into_state(self) -> S {
+        self.state.into_inner().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// The wrapped observable. Messages sent through it directly skip the reducer.
+    pub fn observable(&self) -> &Observable<T> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<T> {
+        &mut self.observable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::reducer::*;
+    use crate::test_util::{received, recorder};
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Cart {
+        Add(&'static str, u32),
+        Remove(&'static str),
+    }
+
+    #[test]
+    fn test_reducer() {
+        let mut cart = Observable::with_reducer(Vec::new(), |items: &mut Vec<(&str, u32)>, event: &Cart| match event {
+            Cart::Add(item, price) => items.push((item, *price)),
+            Cart::Remove(item) => items.retain(|(i, _)| i != item),
+        });
+        let (ob1, weak1) = recorder();
+        cart.register(weak1);
+
+        assert_eq!(cart.send_to_all(&Cart::Add("tea", 4)), 1);
+        cart.send_to_all(&Cart::Add("milk", 2));
+        cart.send_to_all(&Cart::Add("bread", 3));
+        cart.send_to_all(&Cart::Remove("milk"));
+        assert_eq!(*cart.state(), vec![("tea", 4), ("bread", 3)]);
+        assert_eq!(received(&ob1).len(), 4);
+
+        // the state is kept even when nobody listens
+        drop(ob1);
+        assert_eq!(cart.send_to_all(&Cart::Remove("tea")), 0);
+        assert_eq!(cart.into_state(), vec![("bread", 3)]);
+    }
+}
@@ -0,0 +1,184 @@
+use std::{collections::VecDeque, error::Error, fmt, sync::{Mutex, Weak}};
+
+use crate::{Observable, Observer, Subscription};
+
+/// An [`Observable`] that remembers its last `replay_last` messages and replays them
+/// to every observer as it registers, so late subscribers catch up.
+///
+/// Replay happens inside `register`, before it returns, so a new observer always sees
+/// the retained history before any message sent afterwards. `replay_last == 0`
+/// disables replay entirely.
+///
+/// Messages sent through [`send_to_all`](Self::send_to_all) take the number of their
+/// send on the wrapped observable, the one
+/// [`SubscriberSnapshot::last_sequence`](crate::SubscriberSnapshot::last_sequence)
+/// reports: increasing from 1, though not consecutive once replays or direct sends come
+/// in between. A consumer that remembers the [`sequence`](Self::sequence) it last saw can
+/// resume with [`register_since`](Self::register_since).
+pub struct ReplayObservable<T> {
+    observable: Observable<T>,
+    // retained messages with their numbers
+    history: VecDeque<(u64, T)>,
+    replay_last: usize,
+    // number of the last message sent, 0 before the first
+    sequence: u64,
+    // number of the last message no longer retained, 0 while none has been let go
+    dropped: u64,
+}
+
+/// Why [`ReplayObservable::register_since`] could not catch an observer up: some of the
+/// messages it missed are no longer retained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceGap {
+    /// The sequence number asked to resume after.
+    pub since: u64,
+    /// Number of the oldest message still retained, or one past the last sent when none
+    /// is.
+    pub oldest: u64,
+}
+
+impl fmt::Display for SequenceGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "messages after {} are no longer retained, the oldest is {}", self.since, self.oldest)
+    }
+}
+
+impl Error for SequenceGap {}
+
+impl<T: Clone> ReplayObservable<T> {
+    pub fn new(replay_last: usize) -> ReplayObservable<T> {
+        ReplayObservable::with_observable(Observable::new(), replay_last)
+    }
+    // Used by the builder to wrap an already configured observable.
+    pub(crate) fn with_observable(observable: Observable<T>, replay_last: usize) -> ReplayObservable<T> {
+        ReplayObservable {
+            observable,
+            history: VecDeque::with_capacity(replay_last),
+            replay_last,
+            sequence: 0,
+            dropped: 0,
+        }
+    }
+    pub fn register(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        let subscription = self.observable.register(observer);
+        if let Some(i) = self.observable.position(&subscription) {
+            self.history.iter().for_each(|(_, message)| {
+                self.observable.send_to(message, i);
+            });
+        }
+        subscription
+    }
+    /// Registers `observer` and replays only the retained messages numbered after `since`,
+    /// for a consumer resuming where it left off; `since == 0` replays all of them.
+    ///
+    /// Fails without registering anything when a message after `since` has already been
+    /// dropped from the history, so that the consumer can resynchronise some other way.
+    pub fn register_since(&mut self, observer: Weak<Mutex<dyn Observer<T>>>, since: u64) -> Result<Subscription, SequenceGap> {
+        if since < self.dropped {
+            let oldest = self.history.front().map_or(self.sequence + 1, |&(n, _)| n);
+            return Err(SequenceGap { since, oldest });
+        }
+        let subscription = self.observable.register(observer);
+        if let Some(i) = self.observable.position(&subscription) {
+            self.history.iter()
+                .filter(|&&(n, _)| n > since)
+                .for_each(|(_, message)| {
+                    self.observable.send_to(message, i);
+                });
+        }
+        Ok(subscription)
+    }
+    /// Number of the last message sent through [`send_to_all`](Self::send_to_all), or 0
+    /// if none has been.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+    pub fn send_to_all(&mut self, message: &T) -> usize {
+        self.sequence = self.observable.stats().total_sent as u64 + 1;
+        if self.replay_last == 0 {
+            self.dropped = self.sequence;
+        } else {
+            if self.history.len() == self.replay_last {
+                self.dropped = self.history.pop_front().map_or(self.dropped, |(n, _)| n);
+            }
+            self.history.push_back((self.sequence, message.clone()));
+        }
+        self.observable.send_to_all(message)
+    }
+    /// The wrapped observable. Messages sent through it directly are delivered but not
+    /// kept for replay.
+    pub fn observable(&self) -> &Observable<T> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<T> {
+        &mut self.observable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::replay::*;
+    use crate::test_util::{received, recorder};
+
+    #[test]
+    fn test_replay_last() {
+        let mut observable = ReplayObservable::<usize>::new(2);
+        observable.send_to_all(&1);
+        observable.send_to_all(&2);
+        observable.send_to_all(&3);
+
+        let (ob1, ob1w) = recorder();
+        observable.register(ob1w);
+        assert_eq!(received(&ob1), vec![2, 3]);
+
+        observable.send_to_all(&4);
+        assert_eq!(received(&ob1), vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_register_since() {
+        let mut observable = ReplayObservable::<usize>::new(3);
+        for n in 10..14 {
+            observable.send_to_all(&n);
+        }
+        assert_eq!(observable.sequence(), 4);
+
+        // 11, 12 and 13 are retained as messages 2 to 4
+        let (ob1, ob1w) = recorder();
+        assert!(observable.register_since(ob1w, 2).is_ok());
+        assert_eq!(received(&ob1), vec![12, 13]);
+
+        let (ob2, ob2w) = recorder();
+        assert!(observable.register_since(ob2w, 1).is_ok());
+        assert_eq!(received(&ob2), vec![11, 12, 13]);
+
+        let (ob3, ob3w) = recorder();
+        assert!(observable.register_since(ob3w, 4).is_ok());
+        assert_eq!(received(&ob3), Vec::<usize>::new());
+
+        let (ob4, ob4w) = recorder();
+        assert_eq!(observable.register_since(ob4w, 0).err(), Some(SequenceGap { since: 0, oldest: 2 }));
+        assert_eq!(observable.send_to_all(&14), 3);
+        assert_eq!(received(&ob4), Vec::<usize>::new());
+        assert_eq!(received(&ob1), vec![12, 13, 14]);
+
+        // the five replays were sends of their own, and the snapshot agrees on the number
+        assert_eq!(observable.sequence(), 10);
+        let snapshot = observable.observable().snapshot();
+        assert!(snapshot.subscribers.iter().all(|s| s.last_sequence == 10));
+        let (ob5, ob5w) = recorder();
+        assert!(observable.register_since(ob5w, 4).is_ok());
+        assert_eq!(received(&ob5), vec![14]);
+    }
+
+    #[test]
+    fn test_replay_disabled() {
+        let mut observable = ReplayObservable::<usize>::new(0);
+        observable.send_to_all(&1);
+
+        let (ob1, ob1w) = recorder();
+        observable.register(ob1w);
+        assert_eq!(received(&ob1), Vec::<usize>::new());
+    }
+}
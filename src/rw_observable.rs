@@ -0,0 +1,91 @@
+use std::sync::{RwLock, Weak};
+
+use crate::Observer;
+
+/// An [`Observable`](crate::Observable) whose subscribers live behind an [`RwLock`]
+/// instead of a [`Mutex`](std::sync::Mutex).
+///
+/// Delivery still takes a write guard, because [`Observer::notify`] needs `&mut self`.
+/// The benefit is on the reader side: code holding the `Arc<RwLock<_>>` can inspect
+/// observer state through shared read guards without serialising against other readers.
+///
+/// Making `Observable` itself generic over the lock would need a trait over
+/// "something that yields a `DerefMut<Target = dyn Observer<T>>` guard", whose guard type
+/// borrows from the lock (a generic associated type). That churns every method for a
+/// single alternative, so this type is kept separate and `Observable` stays on `Mutex`.
+pub struct RwObservable<T> {
+    subscribers: Vec<Weak<RwLock<dyn Observer<T>>>>,
+}
+
+impl<T> RwObservable<T> {
+    pub fn new() -> RwObservable<T> {
+        RwObservable {
+            subscribers: Vec::new(),
+        }
+    }
+    pub fn register(&mut self, observer: Weak<RwLock<dyn Observer<T>>>) {
+        self.subscribers.push(observer)
+    }
+    pub fn send_to_all(&self, message: &T) -> usize {
+        (0..self.subscribers.len()).fold(0, |acc, i|
+            match self.send_to(message, i) {
+                Some(_) => acc+1,
+                None => acc,
+            }
+        )
+    }
+    pub fn send_to(&self, message: &T, i: usize) -> Option<()> {
+        self.subscribers.get(i)
+            .and_then(|s|
+                s.upgrade()
+            ).and_then(|s| {
+                s.write().ok().as_mut().map(|s| {
+                    s.notify(message);
+                })
+            })
+    }
+}
+
+impl<T> Default for RwObservable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::rw_observable::*;
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct Counter {
+        counter: usize,
+    }
+
+    impl Observer<usize> for Counter {
+        fn notify(&mut self, event: &usize) {
+            self.counter += event;
+        }
+    }
+
+    #[test]
+    fn test_rw_observable() {
+        let mut observable = RwObservable::<usize>::new();
+
+        let ob1: Arc<RwLock<Counter>> = Arc::new(RwLock::new(Counter::default()));
+        let ob1d: Arc<RwLock<dyn Observer<usize>>> = ob1.clone();
+        let ob2: Arc<RwLock<dyn Observer<usize>>> = Arc::new(RwLock::new(Counter::default()));
+        observable.register(Arc::downgrade(&ob1d));
+        observable.register(Arc::downgrade(&ob2));
+        drop(ob2);
+
+        assert_eq!(observable.send_to_all(&2), 1);
+        assert_eq!(observable.send_to_all(&3), 1);
+
+        let read1 = ob1.read().unwrap();
+        let read2 = ob1.read().unwrap();
+        assert_eq!(read1.counter, 5);
+        assert_eq!(read2.counter, 5);
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::{atomic::{AtomicUsize, Ordering}, Mutex, Weak};
+
+use crate::{Observable, Observer, Subscription};
+
+/// An [`Observable`] that only lets the first `n` messages through and silently drops the
+/// rest until [`reset_sampling`](Self::reset_sampling), to keep a hot path from flooding
+/// a debugging observer.
+pub struct SampledObservable<T> {
+    observable: Observable<T>,
+    limit: usize,
+    // messages let through since the last reset; an atomic since sends take `&self`
+    sent: AtomicUsize,
+}
+
+impl<T> SampledObservable<T> {
+    /// Creates an observable forwarding the first `n` messages.
+    pub fn sample_first(n: usize) -> SampledObservable<T> {
+        SampledObservable { observable: Observable::new(), limit: n, sent: AtomicUsize::new(0) }
+    }
+    pub fn register(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.observable.register(observer)
+    }
+    /// Sends `message` to all subscribers if fewer than `n` messages have gone through
+    /// since the last reset, returning how many were notified; 0 once the sample is full.
+    /// A message counts towards the sample whether or not anyone received it.
+    pub fn send_to_all(&self, message: &T) -> usize {
+        let admitted = self.sent
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |sent| (sent < self.limit).then_some(sent + 1))
+            .is_ok();
+        if !admitted {
+            return 0;
+        }
+        self.observable.send_to_all(message)
+    }
+    /// Starts a new sample of `n` messages.
+    pub fn reset_sampling(&mut self) {
+        *self.sent.get_mut() = 0;
+    }
+    /// Messages let through since the last reset, at most `n`.
+    pub fn sampled(&self) -> usize {
+        self.sent.load(Ordering::Acquire)
+    }
+    /// The wrapped observable. Messages sent through it directly are neither limited nor
+    /// counted.
+    pub fn observable(&self) -> &Observable<T> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<T> {
+        &mut self.observable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::sampled::*;
+    use crate::test_util::{received, recorder};
+
+    #[test]
+    fn test_sample_first() {
+        let mut observable = SampledObservable::<u32>::sample_first(2);
+        let (ob1, weak1) = recorder();
+        observable.register(weak1);
+
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(observable.send_to_all(&2), 1);
+        assert_eq!(observable.send_to_all(&3), 0);
+        assert_eq!(observable.sampled(), 2);
+
+        observable.reset_sampling();
+        assert_eq!(observable.send_to_all(&4), 1);
+        assert_eq!(received(&ob1), vec![1, 2, 4]);
+    }
+}
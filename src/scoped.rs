@@ -0,0 +1,108 @@
+/// Receives events borrowed for `'e`, so it may keep the references it is given for as
+/// long as the events live, rather than only for the duration of the call.
+pub trait BorrowObserver<'e, T: ?Sized> {
+    fn notify(&mut self, event: &'e T);
+}
+
+/// An observable for registrations that only last as long as a function call: it
+/// borrows its observers outright instead of going through `Arc<Mutex<_>>`, and hands
+/// them events borrowed for `'e`.
+///
+/// Observers are borrowed for `'o`, so they are usable again once the observable is gone,
+/// which [`scope`](Self::scope) makes explicit:
+///
+/// ```
+/// use rust_exercise_2_event::{BorrowObserver, ScopedObservable};
+///
+/// struct Longest<'e>(Option<&'e str>);
+///
+/// impl<'e> BorrowObserver<'e, str> for Longest<'e> {
+///     fn notify(&mut self, word: &'e str) {
+///         if self.0.is_none_or(|w| w.len() < word.len()) {
+///             self.0 = Some(word);
+///         }
+///     }
+/// }
+///
+/// let text = String::from("the quick brown fox");
+/// let mut longest = Longest(None);
+/// ScopedObservable::scope(|observable| {
+///     observable.register(&mut longest);
+///     text.split(' ').for_each(|word| {
+///         observable.send_to_all(word);
+///     });
+/// });
+/// assert_eq!(longest.0, Some("quick"));
+/// ```
+pub struct ScopedObservable<'o, 'e, T: ?Sized> {
+    observers: Vec<&'o mut (dyn BorrowObserver<'e, T> + 'o)>,
+}
+
+impl<'o, 'e, T: ?Sized> ScopedObservable<'o, 'e, T> {
+    pub fn new() -> ScopedObservable<'o, 'e, T> {
+        ScopedObservable { observers: Vec::new() }
+    }
+    /// Runs `f` with a new observable, which is dropped, releasing every observer it
+    /// borrowed, when `f` returns.
+    pub fn scope<R>(f: impl FnOnce(&mut ScopedObservable<'o, 'e, T>) -> R) -> R {
+        f(&mut ScopedObservable::new())
+    }
+    pub fn register(&mut self, observer: &'o mut (dyn BorrowObserver<'e, T> + 'o)) {
+        self.observers.push(observer);
+    }
+    /// Sends `event` to every observer in registration order, returning how many were
+    /// notified.
+    pub fn send_to_all(&mut self, event: &'e T) -> usize {
+        self.observers.iter_mut().map(|o| o.notify(event)).count()
+    }
+    pub fn len(&self) -> usize {
+        self.observers.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.observers.is_empty()
+    }
+}
+
+impl<T: ?Sized> Default for ScopedObservable<'_, '_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::scoped::*;
+
+    // keeps the events themselves, not copies
+    #[derive(Default)]
+    struct Collect<'e> {
+        seen: Vec<&'e [u8]>,
+    }
+
+    impl<'e> BorrowObserver<'e, [u8]> for Collect<'e> {
+        fn notify(&mut self, event: &'e [u8]) {
+            self.seen.push(event);
+        }
+    }
+
+    #[test]
+    fn test_scoped_observable() {
+        let buffer = [1u8, 2, 3, 4];
+        let mut first = Collect::default();
+        let mut second = Collect::default();
+
+        let notified = ScopedObservable::scope(|observable| {
+            observable.register(&mut first);
+            observable.register(&mut second);
+            observable.send_to_all(&buffer[..2])
+        });
+        assert_eq!(notified, 2);
+
+        // the observable is gone, so the observers are no longer borrowed
+        first.notify(&buffer[2..]);
+        assert_eq!(first.seen, vec![&[1, 2][..], &[3, 4][..]]);
+        assert_eq!(second.seen, vec![&[1, 2][..]]);
+        assert!(std::ptr::eq(first.seen[0], &buffer[..2]));
+    }
+}
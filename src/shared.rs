@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, Weak};
+
+use crate::{Observable, Observer, Subscription};
+
+/// A cloneable handle to an [`Observable`] behind an `Arc<Mutex<_>>`, for the common case
+/// of an observable shared between owners that all register and send.
+///
+/// Each method locks the observable for the duration of the call. That lock is held
+/// while observers run, so an observer must not call back into the same handle from
+/// `notify`: the mutex is not reentrant and the call would deadlock. Observers that
+/// need to reach the observable should keep a [`downgrade`](Self::downgrade)d reference
+/// and use it outside `notify`.
+pub struct SharedObservable<T> {
+    inner: Arc<Mutex<Observable<T>>>,
+}
+
+impl<T> SharedObservable<T> {
+    pub fn new() -> SharedObservable<T> {
+        SharedObservable { inner: Arc::new(Mutex::new(Observable::new())) }
+    }
+    pub fn register(&self, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.lock().register(observer)
+    }
+    pub fn send_to_all(&self, message: &T) -> usize {
+        self.lock().send_to_all(message)
+    }
+    pub fn prune(&self) -> usize {
+        self.lock().prune()
+    }
+    /// A weak reference to the observable that does not keep it alive, for observers to
+    /// hold back to their source.
+    pub fn downgrade(&self) -> Weak<Mutex<Observable<T>>> {
+        Arc::downgrade(&self.inner)
+    }
+    /// Locks the observable for everything the convenience methods do not cover. A
+    /// poisoned lock is recovered, as a panicking observer leaves no broken invariant.
+    pub fn lock(&self) -> MutexGuard<'_, Observable<T>> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Written by hand because `#[derive(Clone)]` would require `T: Clone`.
+impl<T> Clone for SharedObservable<T> {
+    fn clone(&self) -> Self {
+        SharedObservable { inner: self.inner.clone() }
+    }
+}
+
+impl<T> Default for SharedObservable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::shared::*;
+    use crate::test_util::{received, recorder};
+
+    #[test]
+    fn test_shared_observable() {
+        let observable = SharedObservable::<usize>::new();
+        let (ob1, ob1w) = recorder();
+        let (ob2, ob2w) = recorder();
+
+        let handle = observable.clone();
+        std::thread::spawn(move || {
+            handle.register(ob1w);
+        }).join().unwrap();
+        observable.register(ob2w);
+
+        assert_eq!(observable.send_to_all(&1), 2);
+        drop(ob2);
+        assert_eq!(observable.prune(), 1);
+        assert_eq!(observable.lock().live_count(), 1);
+
+        let weak = observable.downgrade();
+        assert!(weak.upgrade().is_some());
+        drop(observable);
+        assert!(weak.upgrade().is_none());
+        assert_eq!(received(&ob1), vec![1]);
+    }
+}
@@ -0,0 +1,119 @@
+use std::sync::atomic::Ordering;
+
+use crate::{GroupId, Observable, ObservableStats};
+
+/// What [`Observable::snapshot`] saw of the observable as a whole.
+///
+/// Plain data throughout, detached from the observable, so it can be shown, compared or
+/// handed to another thread without holding anything.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObservableSnapshot {
+    /// Subscribers registered, live or dead; what [`Observable::len`] returns.
+    pub total: usize,
+    pub live: usize,
+    /// Dropped or unsubscribed entries not yet pruned.
+    pub dead: usize,
+    pub stats: ObservableStats,
+    /// One per entry, in delivery order.
+    pub subscribers: Vec<SubscriberSnapshot>,
+}
+
+/// The state of one subscriber in an [`ObservableSnapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriberSnapshot {
+    /// The slot it occupies, as taken by [`Observable::send_to`].
+    pub index: usize,
+    /// How many times the slot has been handed out before; a handle from another epoch is
+    /// stale.
+    pub epoch: u32,
+    /// The label given to [`Observable::register_named`].
+    pub name: Option<String>,
+    pub group: GroupId,
+    pub alive: bool,
+    /// Whether the observer's mutex is poisoned. Only known while it is alive.
+    pub poisoned: bool,
+    /// Messages delivered to it so far, by any kind of send.
+    pub delivered: u64,
+    /// Number of the last send that delivered to it, 0 if none has. Sends are numbered
+    /// from 1 in the order [`ObservableStats::total_sent`] counts them, the numbering
+    /// [`ReplayObservable::sequence`](crate::ReplayObservable::sequence) uses too; sends
+    /// made at the same time from several threads may share a number.
+    pub last_sequence: u64,
+}
+
+impl<T: ?Sized> Observable<T> {
+    /// Collects the counts, statistics and per-subscriber metadata at this moment, for
+    /// dashboards and debugging. Takes no observer lock, so it is safe to call from
+    /// inside `notify`.
+    pub fn snapshot(&self) -> ObservableSnapshot {
+        let subscribers: Vec<_> = self.entries()
+            .map(|(i, s)| {
+                let observer = s.upgrade();
+                SubscriberSnapshot {
+                    index: i,
+                    epoch: self.epochs[i],
+                    name: s.options.name.clone(),
+                    group: s.options.group,
+                    alive: observer.is_some(),
+                    poisoned: observer.is_some_and(|o| o.is_poisoned()),
+                    delivered: s.delivered.load(Ordering::Relaxed),
+                    last_sequence: s.last_sequence.load(Ordering::Relaxed),
+                }
+            })
+            .collect();
+        let live = subscribers.iter().filter(|s| s.alive).count();
+        ObservableSnapshot {
+            total: subscribers.len(),
+            live,
+            dead: subscribers.len() - live,
+            stats: self.stats(),
+            subscribers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::snapshot::*;
+    use crate::test_util::recorder;
+
+    #[test]
+    fn test_snapshot() {
+        let mut observable = Observable::<u32>::new();
+        let (_first, weak1) = recorder();
+        let (second, weak2) = recorder();
+        observable.register_named("first", weak1);
+        observable.send_to_all(&1);
+        observable.register_named("second", weak2);
+        observable.send_to_all(&2);
+        drop(second);
+        observable.send_to_all(&3);
+
+        let snapshot = observable.snapshot();
+        assert_eq!((snapshot.total, snapshot.live, snapshot.dead), (2, 1, 1));
+        assert_eq!(snapshot.stats, ObservableStats { total_sent: 3, total_delivered: 4, total_dropped: 1 });
+        assert_eq!(snapshot.subscribers, vec![
+            SubscriberSnapshot {
+                index: 0,
+                epoch: 0,
+                name: Some("first".into()),
+                group: GroupId::DEFAULT,
+                alive: true,
+                poisoned: false,
+                delivered: 3,
+                last_sequence: 3,
+            },
+            SubscriberSnapshot {
+                index: 1,
+                epoch: 0,
+                name: Some("second".into()),
+                group: GroupId::DEFAULT,
+                alive: false,
+                poisoned: false,
+                delivered: 1,
+                last_sequence: 2,
+            },
+        ]);
+    }
+}
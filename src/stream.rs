@@ -0,0 +1,246 @@
+use std::{
+    collections::VecDeque,
+    future::Future,
+    pin::Pin,
+    sync::{atomic::{AtomicBool, Ordering}, Arc, Condvar, Mutex, PoisonError},
+    task::{Context, Poll, Waker},
+};
+
+use crate::{Observable, Observer, Options, Subscriber};
+
+struct Shared<T> {
+    queue: VecDeque<T>,
+    waker: Option<Waker>,
+    closed: bool,
+    // set when the stream is dropped, so that a blocked sender gives up
+    detached: bool,
+    bound: Option<(usize, BufferPolicy)>,
+    dropped: usize,
+}
+
+/// What a bounded [`EventStream`] does with an event that arrives while its buffer is
+/// full, see [`Observable::subscribe_stream_bounded`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferPolicy {
+    /// Wait in `notify` until the stream takes an event. The send blocks meanwhile, so
+    /// the stream must be consumed on another thread than the one sending.
+    Block,
+    /// Discard the oldest buffered event to make room.
+    DropOldest,
+    /// Discard the incoming event.
+    DropNewest,
+}
+
+// Owned by the observable; queues clones for the stream and marks it closed when the
+// observable (and with it this observer) goes away.
+struct StreamObserver<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+    // signalled whenever the stream takes an event or goes away
+    space: Arc<Condvar>,
+}
+
+impl<T: Clone + Send> Observer<T> for StreamObserver<T> {
+    fn notify(&mut self, event: &T) {
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        if let Some((capacity, policy)) = shared.bound {
+            if policy == BufferPolicy::Block {
+                shared = self.space
+                    .wait_while(shared, |s| s.queue.len() >= capacity && !s.detached)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+            if shared.queue.len() >= capacity {
+                shared.dropped += 1;
+                match policy {
+                    BufferPolicy::DropOldest => {
+                        shared.queue.pop_front();
+                    }
+                    // `Block` only gets here once the stream is gone
+                    BufferPolicy::Block | BufferPolicy::DropNewest => return,
+                }
+            }
+        }
+        shared.queue.push_back(event.clone());
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for StreamObserver<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        shared.closed = true;
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Stream of the events sent to an [`Observable`], returned by
+/// [`Observable::subscribe_stream`].
+///
+/// The `futures` crate is not a dependency, so this does not implement
+/// `futures::Stream`; [`poll_next`](Self::poll_next) has the same signature, making a
+/// wrapper trivial, and [`recv`](Self::recv) covers plain `async` code.
+///
+/// The buffer of [`subscribe_stream`](Observable::subscribe_stream) is unbounded:
+/// events sent faster than they are consumed accumulate in memory until the stream
+/// catches up or is dropped.
+/// [`subscribe_stream_bounded`](Observable::subscribe_stream_bounded) caps it instead.
+pub struct EventStream<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+    space: Arc<Condvar>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl<T> EventStream<T> {
+    /// Yields the next event, or `None` once the observable has been dropped and every
+    /// buffered event has been taken.
+    pub fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        match shared.queue.pop_front() {
+            Some(event) => {
+                self.space.notify_all();
+                Poll::Ready(Some(event))
+            }
+            None if shared.closed => Poll::Ready(None),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+    pub fn recv(&mut self) -> Recv<'_, T> {
+        Recv { stream: self }
+    }
+    /// Number of events a bounded stream has discarded because its buffer was full.
+    pub fn dropped(&self) -> usize {
+        self.shared.lock().unwrap_or_else(PoisonError::into_inner).dropped
+    }
+}
+
+// Unsubscribes the forwarding observer, which `prune` then removes, and releases a
+// sender blocked on the full buffer.
+impl<T> Drop for EventStream<T> {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Release);
+        self.shared.lock().unwrap_or_else(PoisonError::into_inner).detached = true;
+        self.space.notify_all();
+    }
+}
+
+/// Future returned by [`EventStream::recv`].
+pub struct Recv<'a, T> {
+    stream: &'a mut EventStream<T>,
+}
+
+impl<T> Future for Recv<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        Pin::new(&mut *self.stream).poll_next(cx)
+    }
+}
+
+// `Clone` keeps this `Sized`: the stream yields owned copies of the events
+impl<T: Clone + Send + 'static> Observable<T> {
+    /// Returns a stream yielding a clone of every message sent from now on.
+    pub fn subscribe_stream(&mut self) -> EventStream<T> {
+        self.subscribe_to_stream(None)
+    }
+    /// Like [`subscribe_stream`](Self::subscribe_stream), but buffers at most `capacity`
+    /// events and applies `policy` to the ones arriving while it is full. Discarded
+    /// events are counted by [`EventStream::dropped`].
+    ///
+    /// A `capacity` of 0 is raised to 1: an event has to be buffered until the stream
+    /// takes it, and with no room at all a [`BufferPolicy::Block`] send would never return.
+    pub fn subscribe_stream_bounded(&mut self, capacity: usize, policy: BufferPolicy) -> EventStream<T> {
+        self.subscribe_to_stream(Some((capacity.max(1), policy)))
+    }
+    fn subscribe_to_stream(&mut self, bound: Option<(usize, BufferPolicy)>) -> EventStream<T> {
+        let shared = Arc::new(Mutex::new(Shared {
+            queue: VecDeque::new(),
+            waker: None,
+            closed: false,
+            detached: false,
+            bound,
+            dropped: 0,
+        }));
+        let space = Arc::new(Condvar::new());
+        let observer = StreamObserver { shared: shared.clone(), space: space.clone() };
+        let subscription = self.insert(Subscriber::Owned(Arc::new(Mutex::new(observer))), Options::default());
+        EventStream { shared, space, cancelled: subscription.cancelled.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::stream::*;
+    use crate::async_observable::tests::block_on;
+
+    #[test]
+    fn test_subscribe_stream() {
+        let mut observable = Observable::<usize>::new();
+        let mut stream = observable.subscribe_stream();
+
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(observable.send_to_all(&2), 1);
+        assert_eq!(block_on(stream.recv()), Some(1));
+        assert_eq!(block_on(stream.recv()), Some(2));
+
+        drop(observable);
+        assert_eq!(block_on(stream.recv()), None);
+    }
+
+    #[test]
+    fn test_subscribe_stream_bounded() {
+        let mut observable = Observable::<usize>::new();
+        let mut oldest = observable.subscribe_stream_bounded(1, BufferPolicy::DropOldest);
+        let mut newest = observable.subscribe_stream_bounded(1, BufferPolicy::DropNewest);
+
+        for n in 1..=3 {
+            assert_eq!(observable.send_to_all(&n), 2);
+        }
+        assert_eq!(oldest.dropped(), 2);
+        assert_eq!(newest.dropped(), 2);
+        assert_eq!(block_on(oldest.recv()), Some(3));
+        assert_eq!(block_on(newest.recv()), Some(1));
+    }
+
+    #[test]
+    fn test_subscribe_stream_bounded_zero_capacity() {
+        let mut observable = Observable::<usize>::new();
+        let mut oldest = observable.subscribe_stream_bounded(0, BufferPolicy::DropOldest);
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(observable.send_to_all(&2), 1);
+        assert_eq!(oldest.dropped(), 1);
+        assert_eq!(block_on(oldest.recv()), Some(2));
+
+        let mut observable = Observable::<usize>::new();
+        let mut blocking = observable.subscribe_stream_bounded(0, BufferPolicy::Block);
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(block_on(blocking.recv()), Some(1));
+    }
+
+    #[test]
+    fn test_subscribe_stream_bounded_block() {
+        let mut observable = Observable::<usize>::new();
+        let mut stream = observable.subscribe_stream_bounded(1, BufferPolicy::Block);
+
+        let sender = std::thread::spawn(move || (1..=3).map(|n| observable.send_to_all(&n)).sum::<usize>());
+        let received: Vec<_> = (0..3).map(|_| block_on(stream.recv())).collect();
+        assert_eq!(sender.join().unwrap(), 3);
+        assert_eq!(received, vec![Some(1), Some(2), Some(3)]);
+        assert_eq!(stream.dropped(), 0);
+    }
+
+    #[test]
+    fn test_stream_dropped() {
+        let mut observable = Observable::<usize>::new();
+        drop(observable.subscribe_stream());
+
+        assert_eq!(observable.send_to_all(&1), 0);
+        assert_eq!(observable.prune(), 1);
+    }
+}
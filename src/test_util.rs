@@ -0,0 +1,28 @@
+use std::sync::{Arc, Mutex, Weak};
+
+use crate::Observer;
+
+// Observer fixture shared by the module tests: keeps a clone of every event.
+pub(crate) struct Recorder<E> {
+    received: Vec<E>,
+}
+
+impl<E: Clone + Send> Observer<E> for Recorder<E> {
+    fn notify(&mut self, event: &E) {
+        self.received.push(event.clone());
+    }
+}
+
+// What `register` takes.
+type WeakObserver<E> = Weak<Mutex<dyn Observer<E>>>;
+
+// A new recorder together with the weak `dyn Observer` handle to register it with.
+pub(crate) fn recorder<E: Clone + Send + 'static>() -> (Arc<Mutex<Recorder<E>>>, WeakObserver<E>) {
+    let recorder = Arc::new(Mutex::new(Recorder { received: Vec::new() }));
+    let weak: Weak<Mutex<Recorder<E>>> = Arc::downgrade(&recorder);
+    (recorder, weak)
+}
+
+pub(crate) fn received<E: Clone>(recorder: &Mutex<Recorder<E>>) -> Vec<E> {
+    recorder.lock().unwrap().received.clone()
+}
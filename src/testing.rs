@@ -0,0 +1,96 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{Observable, Observer};
+
+/// An observer for tests that keeps a clone of every event it receives. Only compiled
+/// with the `testing` feature.
+///
+/// ```
+/// use rust_exercise_2_event::{Observable, TestObserver};
+///
+/// let mut observable = Observable::new();
+/// let observer = TestObserver::shared();
+/// observable.register_concrete(&observer);
+/// observable.assert_delivered(&"ping", 1);
+/// assert_eq!(observer.lock().unwrap().received(), ["ping"]);
+/// ```
+#[derive(Debug)]
+pub struct TestObserver<T> {
+    received: Vec<T>,
+}
+
+impl<T> TestObserver<T> {
+    pub fn new() -> TestObserver<T> {
+        TestObserver { received: Vec::new() }
+    }
+    /// A new observer behind the `Arc<Mutex<_>>` that registering it takes.
+    pub fn shared() -> Arc<Mutex<TestObserver<T>>> {
+        Arc::new(Mutex::new(TestObserver::new()))
+    }
+    /// Every event received so far, oldest first.
+    pub fn received(&self) -> &[T] {
+        &self.received
+    }
+    /// Hands over the events received so far, leaving none.
+    pub fn take(&mut self) -> Vec<T> {
+        std::mem::take(&mut self.received)
+    }
+}
+
+impl<T> Default for TestObserver<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Send> Observer<T> for TestObserver<T> {
+    fn notify(&mut self, event: &T) {
+        self.received.push(event.clone());
+    }
+}
+
+impl<T: ?Sized> Observable<T> {
+    /// Sends `message` to all subscribers and panics unless exactly `expected` of them were
+    /// notified. Only compiled with the `testing` feature.
+    #[track_caller]
+    pub fn assert_delivered(&self, message: &T, expected: usize) {
+        let delivered = self.send_to_all(message);
+        assert!(
+            delivered == expected,
+            "expected the message to reach {expected} subscriber(s), but it reached {delivered} of {} registered",
+            self.len(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::testing::*;
+    use std::panic::{self, AssertUnwindSafe};
+
+    #[test]
+    fn test_test_observer() {
+        let mut observable = Observable::<u8>::new();
+        let before = TestObserver::shared();
+        let after = TestObserver::shared();
+        observable.register_concrete(&before);
+        observable.assert_delivered(&1, 1);
+        observable.register_concrete(&after);
+        observable.assert_delivered(&2, 2);
+
+        assert_eq!(before.lock().unwrap().received(), [1, 2]);
+        assert_eq!(after.lock().unwrap().take(), vec![2]);
+        assert!(after.lock().unwrap().received().is_empty());
+    }
+
+    #[test]
+    fn test_assert_delivered_panics() {
+        let observable = Observable::<u8>::new();
+        let panic = panic::catch_unwind(AssertUnwindSafe(|| observable.assert_delivered(&1, 1))).unwrap_err();
+        assert_eq!(
+            panic.downcast_ref::<String>().map(String::as_str),
+            Some("expected the message to reach 1 subscriber(s), but it reached 0 of 0 registered"),
+        );
+    }
+}
@@ -0,0 +1,129 @@
+use std::{
+    sync::mpsc::{self, Sender},
+    thread::{self, JoinHandle},
+};
+
+use crate::Observer;
+
+/// Runs an observer on a worker thread of its own: `notify` only clones the event into a
+/// channel, so sends return without waiting for a slow observer, which sees the events
+/// later, in order.
+///
+/// Send results and statistics count the wrapper as notified once the event is queued,
+/// not once the worker has handled it. [`shutdown`](Self::shutdown) waits for the worker
+/// to get through the queue; dropping the wrapper without it leaves the worker to finish
+/// in the background.
+pub struct ThreadedObserver<T> {
+    // `None` once shut down
+    sender: Option<Sender<T>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl<T: Clone + Send + 'static> ThreadedObserver<T> {
+    /// Spawns the worker thread, which owns `observer` from now on.
+    pub fn new<O: Observer<T> + 'static>(mut observer: O) -> ThreadedObserver<T> {
+        let (sender, receiver) = mpsc::channel::<T>();
+        let worker = thread::spawn(move || {
+            for event in receiver {
+                observer.notify(&event);
+            }
+            observer.flush();
+        });
+        ThreadedObserver { sender: Some(sender), worker: Some(worker) }
+    }
+}
+
+impl<T> ThreadedObserver<T> {
+    /// Stops taking events and blocks until the worker has handled every one already
+    /// queued, then flushes and drops the observer. Events notified afterwards are
+    /// discarded. Returns `false` if the observer panicked, and on later calls.
+    pub fn shutdown(&mut self) -> bool {
+        self.sender = None;
+        self.worker.take().is_some_and(|worker| worker.join().is_ok())
+    }
+    /// Whether the worker is still taking events: it has not been shut down, nor has the
+    /// observer panicked.
+    pub fn is_running(&self) -> bool {
+        self.worker.as_ref().is_some_and(|worker| !worker.is_finished())
+    }
+}
+
+impl<T: Clone + Send> Observer<T> for ThreadedObserver<T> {
+    fn notify(&mut self, event: &T) {
+        if let Some(sender) = &self.sender {
+            // fails only once the worker is gone, after a panic
+            let _ = sender.send(event.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::threaded::*;
+    use crate::Observable;
+    use std::sync::{mpsc::Receiver, Arc, Barrier, Mutex};
+    use std::thread::ThreadId;
+
+    // reports each event with the thread it was handled on, once let through the barrier
+    struct Slow {
+        gate: Arc<Barrier>,
+        seen: Sender<(ThreadId, u32)>,
+    }
+
+    impl Observer<u32> for Slow {
+        fn notify(&mut self, event: &u32) {
+            self.gate.wait();
+            let _ = self.seen.send((thread::current().id(), *event));
+        }
+    }
+
+    fn slow() -> (ThreadedObserver<u32>, Arc<Barrier>, Receiver<(ThreadId, u32)>) {
+        let gate = Arc::new(Barrier::new(2));
+        let (seen, received) = mpsc::channel();
+        (ThreadedObserver::new(Slow { gate: gate.clone(), seen }), gate, received)
+    }
+
+    #[test]
+    fn test_threaded_observer() {
+        let (threaded, gate, received) = slow();
+        let threaded = Arc::new(Mutex::new(threaded));
+        let mut observable = Observable::new();
+        observable.register_concrete(&threaded);
+
+        // both sends return while the worker is still held at the barrier
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(observable.send_to_all(&2), 1);
+        assert!(received.try_recv().is_err());
+
+        gate.wait();
+        gate.wait();
+        let handled: Vec<_> = received.iter().take(2).collect();
+        assert_eq!(handled.iter().map(|&(_, e)| e).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(handled.iter().all(|&(id, _)| id != thread::current().id()));
+
+        let mut threaded = threaded.lock().unwrap();
+        assert!(threaded.is_running());
+        assert!(threaded.shutdown());
+        assert!(!threaded.is_running());
+        // the observer went with the worker
+        assert!(received.recv().is_err());
+        assert!(!threaded.shutdown());
+    }
+
+    #[test]
+    fn test_threaded_shutdown_drains_queue() {
+        let (mut threaded, gate, received) = slow();
+        threaded.notify(&1);
+        threaded.notify(&2);
+
+        let releaser = thread::spawn(move || {
+            gate.wait();
+            gate.wait();
+        });
+        assert!(threaded.shutdown());
+        releaser.join().unwrap();
+        threaded.notify(&3);
+        assert_eq!(received.iter().map(|(_, e)| e).collect::<Vec<_>>(), vec![1, 2]);
+    }
+}
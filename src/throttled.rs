@@ -0,0 +1,74 @@
+use std::{sync::{Mutex, PoisonError, Weak}, time::{Duration, Instant}};
+
+use crate::{Observable, Observer, Subscription};
+
+/// An [`Observable`] that lets at most one message through per `min_interval`.
+///
+/// Throttling is leading-edge: the first message after a quiet period is delivered
+/// at once and anything sent within `min_interval` of it is dropped, not deferred, so
+/// a burst is represented by its first message.
+pub struct ThrottledObservable<T> {
+    observable: Observable<T>,
+    min_interval: Duration,
+    last_delivery: Mutex<Option<Instant>>,
+}
+
+impl<T> ThrottledObservable<T> {
+    pub fn new(min_interval: Duration) -> ThrottledObservable<T> {
+        ThrottledObservable {
+            observable: Observable::new(),
+            min_interval,
+            last_delivery: Mutex::new(None),
+        }
+    }
+    pub fn register(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.observable.register(observer)
+    }
+    /// Sends `message` to all subscribers unless the previous delivery was less than
+    /// `min_interval` ago, returning whether it went through.
+    pub fn send_throttled(&self, message: &T) -> bool {
+        self.send_throttled_at(message, Instant::now())
+    }
+    // `send_throttled` with the clock passed in, so tests need not sleep.
+    fn send_throttled_at(&self, message: &T, now: Instant) -> bool {
+        {
+            let mut last_delivery = self.last_delivery.lock().unwrap_or_else(PoisonError::into_inner);
+            if last_delivery.is_some_and(|last| now.duration_since(last) < self.min_interval) {
+                return false;
+            }
+            *last_delivery = Some(now);
+        }
+        self.observable.send_to_all(message);
+        true
+    }
+    /// The wrapped observable. Messages sent through it directly bypass the throttle and
+    /// do not restart the interval.
+    pub fn observable(&self) -> &Observable<T> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<T> {
+        &mut self.observable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::throttled::*;
+    use crate::test_util::{received, recorder};
+
+    #[test]
+    fn test_send_throttled() {
+        let mut observable = ThrottledObservable::<usize>::new(Duration::from_millis(50));
+        let (ob1, ob1w) = recorder();
+        observable.register(ob1w);
+
+        let start = Instant::now();
+        assert!(observable.send_throttled_at(&1, start));
+        assert!(!observable.send_throttled_at(&2, start + Duration::from_millis(49)));
+        assert!(observable.send_throttled_at(&3, start + Duration::from_millis(50)));
+        assert!(!observable.send_throttled_at(&4, start + Duration::from_millis(60)));
+
+        assert_eq!(received(&ob1), vec![1, 3]);
+    }
+}
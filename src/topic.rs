@@ -0,0 +1,103 @@
+use std::{marker::PhantomData, sync::{Mutex, Weak}};
+
+use crate::{Observable, Observer, Subscription};
+
+/// An [`Observable`] whose topics are values of a type `K` of your choosing, typically an
+/// enum, instead of the strings of [`Observable::register_on`]; a misspelt topic is then a
+/// compile error rather than a silent miss. `()` serves as the topic type when subscribers
+/// need no topics at all.
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use rust_exercise_2_event::{FnObserver, Observer, TopicObservable};
+///
+/// #[derive(PartialEq, Eq)]
+/// enum Channel {
+///     Audio,
+///     Video,
+/// }
+///
+/// let mut observable = TopicObservable::<Channel, u32>::new();
+/// let audio: Arc<Mutex<dyn Observer<u32>>> = Arc::new(Mutex::new(FnObserver::new(|_: &u32| {})));
+/// observable.register_on(Channel::Audio, Arc::downgrade(&audio));
+/// assert_eq!(observable.send_to_topic(&Channel::Audio, &1), 1);
+/// assert_eq!(observable.send_to_topic(&Channel::Video, &2), 0);
+/// ```
+pub struct TopicObservable<K, T> {
+    observable: Observable<T>,
+    _key: PhantomData<fn(K)>,
+}
+
+impl<K: Eq + Send + Sync + 'static, T> TopicObservable<K, T> {
+    pub fn new() -> TopicObservable<K, T> {
+        TopicObservable { observable: Observable::new(), _key: PhantomData }
+    }
+    /// Registers `observer` on no topic: it only receives [`send_to_all`](Self::send_to_all).
+    pub fn register(&mut self, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.observable.register(observer)
+    }
+    /// Registers `observer` on `topic`: it receives messages sent to that topic, plus
+    /// everything sent to all subscribers.
+    pub fn register_on(&mut self, topic: K, observer: Weak<Mutex<dyn Observer<T>>>) -> Subscription {
+        self.observable.register_keyed(topic, observer)
+    }
+    /// Sends `message` to the subscribers registered on `topic`, returning how many were
+    /// notified. Nested sends are ignored as with [`Observable::send_to_all`].
+    pub fn send_to_topic(&self, topic: &K, message: &T) -> usize {
+        self.observable.send_keyed(message, |key| key.downcast_ref::<K>() == Some(topic))
+    }
+    /// Sends `message` to every subscriber, whatever its topic.
+    pub fn send_to_all(&self, message: &T) -> usize {
+        self.observable.send_to_all(message)
+    }
+    /// The wrapped observable, for everything this does not wrap.
+    pub fn observable(&self) -> &Observable<T> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<T> {
+        &mut self.observable
+    }
+}
+
+impl<K: Eq + Send + Sync + 'static, T> Default for TopicObservable<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::topic::*;
+    use crate::test_util::{received, recorder};
+
+    #[derive(Debug, PartialEq, Eq, Hash)]
+    enum Side {
+        Left,
+        Right,
+    }
+
+    #[test]
+    fn test_topic_observable() {
+        let mut observable = TopicObservable::<Side, u8>::new();
+        let (left, weak1) = recorder();
+        let (right, weak2) = recorder();
+        let (all, weak3) = recorder();
+        observable.register_on(Side::Left, weak1);
+        observable.register_on(Side::Right, weak2);
+        observable.register(weak3);
+
+        assert_eq!(observable.send_to_topic(&Side::Left, &1), 1);
+        assert_eq!(observable.send_to_topic(&Side::Right, &2), 1);
+        assert_eq!(observable.send_to_all(&3), 3);
+        assert_eq!(received(&left), vec![1, 3]);
+        assert_eq!(received(&right), vec![2, 3]);
+        assert_eq!(received(&all), vec![3]);
+
+        // string topics registered on the same observable never match a typed one
+        let (named, weak4) = recorder();
+        observable.observable_mut().register_on("Left", weak4);
+        assert_eq!(observable.send_to_topic(&Side::Left, &4), 1);
+        assert!(received(&named).is_empty());
+    }
+}
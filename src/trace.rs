@@ -0,0 +1,130 @@
+use crate::{DeliveryResult, Observable};
+
+#[cfg(feature = "tracing")]
+/// Receives structured events about each dispatch of an [`Observable`], installed with
+/// [`Observable::with_tracer`].
+///
+/// A dispatch is bracketed by [`dispatch_start`](Self::dispatch_start) and
+/// [`dispatch_end`](Self::dispatch_end), with one [`delivery`](Self::delivery) per
+/// subscriber in between, so an implementation can open a span at the start and close it
+/// at the end. Every method defaults to doing nothing. Only compiled with the `tracing`
+/// feature; without it the observable carries no tracer and the calls are compiled out.
+pub trait Tracer: Send + Sync {
+    /// A send is about to reach `subscribers` entries, live or dead.
+    fn dispatch_start(&self, _subscribers: usize) {}
+    /// The subscriber in slot `index` was tried, with the given outcome.
+    fn delivery(&self, _index: usize, _result: DeliveryResult) {}
+    /// The send finished, having notified `delivered` subscribers.
+    fn dispatch_end(&self, _delivered: usize) {}
+}
+
+#[cfg(feature = "tracing")]
+impl<T: ?Sized> Observable<T> {
+    /// Reports every [`send_to_all`](Self::send_to_all), including its
+    /// [`send_to_all_detailed`](Self::send_to_all_detailed) and
+    /// [`send_to_all_complete`](Self::send_to_all_complete) forms, and every
+    /// [`send_collect`](Self::send_collect), [`query_all`](Self::query_all) and
+    /// [`send_collect_replies`](Self::send_collect_replies) to `tracer`. Nested sends that are ignored
+    /// are not reported.
+    pub fn with_tracer(mut self, tracer: impl Tracer + 'static) -> Observable<T> {
+        self.tracer = Some(Box::new(tracer));
+        self
+    }
+    pub(crate) fn trace_start(&self, subscribers: usize) {
+        if let Some(tracer) = &self.tracer {
+            tracer.dispatch_start(subscribers);
+        }
+    }
+    pub(crate) fn trace_delivery(&self, index: usize, result: DeliveryResult) {
+        if let Some(tracer) = &self.tracer {
+            tracer.delivery(index, result);
+        }
+    }
+    pub(crate) fn trace_end(&self, delivered: usize) {
+        if let Some(tracer) = &self.tracer {
+            tracer.dispatch_end(delivered);
+        }
+    }
+}
+
+// Without the feature the hooks are empty and inline away, leaving no trace in the sends.
+#[cfg(not(feature = "tracing"))]
+impl<T: ?Sized> Observable<T> {
+    #[inline(always)]
+    pub(crate) fn trace_start(&self, _subscribers: usize) {}
+    #[inline(always)]
+    pub(crate) fn trace_delivery(&self, _index: usize, _result: DeliveryResult) {}
+    #[inline(always)]
+    pub(crate) fn trace_end(&self, _delivered: usize) {}
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+
+    use crate::trace::*;
+    use crate::test_util::recorder;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, PartialEq)]
+    enum Record {
+        Start(usize),
+        Delivery(usize, DeliveryResult),
+        End(usize),
+    }
+
+    // collects what it is told, shared with the test through the `Arc`
+    #[derive(Clone, Default)]
+    struct Collector(Arc<Mutex<Vec<Record>>>);
+
+    impl Tracer for Collector {
+        fn dispatch_start(&self, subscribers: usize) {
+            self.0.lock().unwrap().push(Record::Start(subscribers));
+        }
+        fn delivery(&self, index: usize, result: DeliveryResult) {
+            self.0.lock().unwrap().push(Record::Delivery(index, result));
+        }
+        fn dispatch_end(&self, delivered: usize) {
+            self.0.lock().unwrap().push(Record::End(delivered));
+        }
+    }
+
+    #[test]
+    fn test_tracer() {
+        let collector = Collector::default();
+        let mut observable = Observable::<usize>::new().with_tracer(collector.clone());
+        let (_ob1, weak1) = recorder();
+        let (ob2, weak2) = recorder();
+        observable.register(weak1);
+        observable.register(weak2);
+        drop(ob2);
+
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(*collector.0.lock().unwrap(), vec![
+            Record::Start(2),
+            Record::Delivery(0, DeliveryResult::Delivered),
+            Record::Delivery(1, DeliveryResult::Dropped),
+            Record::End(1),
+        ]);
+    }
+
+    #[test]
+    fn test_tracer_collecting_sends() {
+        let collector = Collector::default();
+        let mut observable = Observable::<usize>::new().with_tracer(collector.clone());
+        let (_ob1, weak1) = recorder();
+        observable.register(weak1);
+
+        observable.send_collect(&1);
+        let one_send = vec![Record::Start(1), Record::Delivery(0, DeliveryResult::Delivered), Record::End(1)];
+        let records = std::mem::take(&mut *collector.0.lock().unwrap());
+        assert_eq!(records, one_send);
+
+        observable.query_all::<u32>(&2);
+        let records = std::mem::take(&mut *collector.0.lock().unwrap());
+        assert_eq!(records, one_send);
+
+        observable.send_collect_replies::<u32>(&3, std::time::Duration::ZERO);
+        let records = std::mem::take(&mut *collector.0.lock().unwrap());
+        assert_eq!(records, one_send);
+    }
+}
@@ -0,0 +1,102 @@
+use std::{marker::PhantomData, sync::{Mutex, Weak}};
+
+use crate::Observer;
+
+/// An [`Observable`](crate::Observable) for a single concrete observer type `O`.
+///
+/// Subscribers are stored as `Weak<Mutex<O>>`, so `notify` is dispatched statically and
+/// can be inlined; there is no `dyn Observer<T>` vtable on the hot path. The price is
+/// that every subscriber has the same type, so this suits homogeneous sets of observers
+/// rather than replacing `Observable`.
+pub struct TypedObservable<T, O> {
+    subscribers: Vec<Weak<Mutex<O>>>,
+    _marker: PhantomData<fn(&T)>,
+}
+
+impl<T, O: Observer<T>> TypedObservable<T, O> {
+    pub fn new() -> TypedObservable<T, O> {
+        TypedObservable {
+            subscribers: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+    pub fn register(&mut self, observer: Weak<Mutex<O>>) {
+        self.subscribers.push(observer)
+    }
+    /// Sends `message` to every live subscriber in registration order, returning how
+    /// many were notified.
+    pub fn send_to_all(&self, message: &T) -> usize {
+        self.subscribers.iter()
+            .filter(|s| Self::deliver(s, message))
+            .count()
+    }
+    pub fn send_to(&self, message: &T, i: usize) -> Option<()> {
+        self.subscribers.get(i)
+            .filter(|s| Self::deliver(s, message))
+            .map(|_| ())
+    }
+    /// Removes every subscriber that has been dropped, returning how many were removed.
+    pub fn prune(&mut self) -> usize {
+        let before = self.subscribers.len();
+        self.subscribers.retain(|s| s.strong_count() > 0);
+        before - self.subscribers.len()
+    }
+    fn deliver(subscriber: &Weak<Mutex<O>>, message: &T) -> bool {
+        subscriber.upgrade()
+            .and_then(|o| o.lock().ok().map(|mut o| o.notify(message)))
+            .is_some()
+    }
+}
+
+impl<T, O: Observer<T>> Default for TypedObservable<T, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::typed::*;
+    use crate::Observable;
+    use std::sync::Arc;
+
+    #[derive(Default)]
+    struct Counter {
+        counter: usize,
+    }
+
+    impl Observer<usize> for Counter {
+        fn notify(&mut self, event: &usize) {
+            self.counter += event;
+        }
+    }
+
+    #[test]
+    fn test_typed_observable_matches_dyn() {
+        let mut typed = TypedObservable::<usize, Counter>::new();
+        let mut dynamic = Observable::<usize>::new();
+
+        let mut typed_obs: Vec<Arc<Mutex<Counter>>> = (0..3).map(|_| Arc::new(Mutex::new(Counter::default()))).collect();
+        let mut dyn_obs: Vec<Arc<Mutex<Counter>>> = (0..3).map(|_| Arc::new(Mutex::new(Counter::default()))).collect();
+        typed_obs.iter().for_each(|o| typed.register(Arc::downgrade(o)));
+        dyn_obs.iter().for_each(|o| {
+            let o: Arc<Mutex<dyn Observer<usize>>> = o.clone();
+            dynamic.register(Arc::downgrade(&o));
+        });
+
+        typed_obs.remove(1);
+        dyn_obs.remove(1);
+
+        for message in [1, 2, 3] {
+            assert_eq!(typed.send_to_all(&message), dynamic.send_to_all(&message));
+        }
+        assert_eq!(typed.send_to(&10, 1), dynamic.send_to(&10, 1));
+        assert_eq!(typed.send_to(&10, 2), dynamic.send_to(&10, 2));
+        assert_eq!(typed.prune(), dynamic.prune());
+
+        let counters = |obs: &[Arc<Mutex<Counter>>]| obs.iter().map(|o| o.lock().unwrap().counter).collect::<Vec<_>>();
+        assert_eq!(counters(&typed_obs), vec![6, 16]);
+        assert_eq!(counters(&typed_obs), counters(&dyn_obs));
+    }
+}
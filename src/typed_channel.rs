@@ -0,0 +1,114 @@
+use std::sync::{Arc, Mutex};
+
+use crate::{Observable, Observer, Subscription};
+
+/// A named pipe for one event type: a narrower face for an [`Observable`] when a program
+/// wires up several of them, so that the channel's type is all there is to get right.
+///
+/// [`subscribe`](Self::subscribe) takes the concrete observer, so registering it on the
+/// wrong channel fails to compile at the call, rather than at some coercion to
+/// `dyn Observer<_>` written elsewhere:
+///
+/// ```
+/// use std::sync::{Arc, Mutex};
+/// use rust_exercise_2_event::{Channel, Observer};
+///
+/// struct Clicks(u32);
+///
+/// impl Observer<(i32, i32)> for Clicks {
+///     fn notify(&mut self, _position: &(i32, i32)) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// let mut clicks = Channel::<(i32, i32)>::new();
+/// let counter = Arc::new(Mutex::new(Clicks(0)));
+/// clicks.subscribe(&counter);
+/// assert_eq!(clicks.publish(&(3, 4)), 1);
+/// assert_eq!(counter.lock().unwrap().0, 1);
+/// ```
+///
+/// ```compile_fail
+/// use std::sync::{Arc, Mutex};
+/// use rust_exercise_2_event::{Channel, Observer};
+///
+/// struct Clicks;
+///
+/// impl Observer<(i32, i32)> for Clicks {
+///     fn notify(&mut self, _position: &(i32, i32)) {}
+/// }
+///
+/// let mut keys = Channel::<char>::new();
+/// keys.subscribe(&Arc::new(Mutex::new(Clicks)));
+/// ```
+pub struct Channel<T> {
+    observable: Observable<T>,
+}
+
+impl<T> Channel<T> {
+    pub fn new() -> Channel<T> {
+        Channel { observable: Observable::new() }
+    }
+    /// Subscribes `observer` for as long as it is kept alive elsewhere.
+    pub fn subscribe<O: Observer<T> + 'static>(&mut self, observer: &Arc<Mutex<O>>) -> Subscription {
+        self.observable.register_concrete(observer)
+    }
+    /// Removes a subscription, returning whether it was still registered.
+    pub fn unsubscribe(&mut self, subscription: Subscription) -> bool {
+        self.observable.unregister(subscription)
+    }
+    /// Delivers `event` to every subscriber, returning how many were notified.
+    pub fn publish(&self, event: &T) -> usize {
+        self.observable.send_to_all(event)
+    }
+    /// The underlying observable, for everything the channel does not wrap.
+    pub fn observable(&self) -> &Observable<T> {
+        &self.observable
+    }
+    pub fn observable_mut(&mut self) -> &mut Observable<T> {
+        &mut self.observable
+    }
+}
+
+impl<T> Default for Channel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::typed_channel::*;
+
+    #[derive(Default)]
+    struct Log(Vec<String>);
+
+    impl Observer<u32> for Log {
+        fn notify(&mut self, event: &u32) {
+            self.0.push(format!("u32 {}", event));
+        }
+    }
+
+    impl Observer<&'static str> for Log {
+        fn notify(&mut self, event: &&'static str) {
+            self.0.push(format!("str {}", event));
+        }
+    }
+
+    #[test]
+    fn test_channels() {
+        let mut numbers = Channel::<u32>::new();
+        let mut names = Channel::<&'static str>::new();
+        // one observer on two channels, picking the impl for each
+        let log = Arc::new(Mutex::new(Log::default()));
+        let on_numbers = numbers.subscribe(&log);
+        names.subscribe(&log);
+
+        assert_eq!(numbers.publish(&1), 1);
+        assert_eq!(names.publish(&"a"), 1);
+        assert!(numbers.unsubscribe(on_numbers));
+        assert_eq!(numbers.publish(&2), 0);
+        assert_eq!(log.lock().unwrap().0, ["u32 1", "str a"]);
+    }
+}
@@ -0,0 +1,139 @@
+use std::{
+    fmt::Display,
+    io::{self, Write},
+};
+
+use crate::Observer;
+
+/// What a [`WriterObserver`] does when its writer fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteErrorPolicy {
+    /// Panic in `notify`, poisoning the observer's mutex.
+    Panic,
+    /// Drop the error and carry on with the next event.
+    Ignore,
+    /// Keep the error for [`WriterObserver::last_error`], replacing any earlier one, and
+    /// carry on with the next event.
+    #[default]
+    StoreLastError,
+}
+
+/// Writes each event it is notified of to `W`, formatted with [`Display`] and followed by
+/// a newline: a "log every event" subscriber for any `T: Display`.
+///
+/// The event type is left to the `Observer` impl rather than fixed on the struct, so one
+/// writer observer can be registered on observables of different types. [`Observer::flush`]
+/// flushes the writer.
+pub struct WriterObserver<W> {
+    writer: W,
+    policy: WriteErrorPolicy,
+    last_error: Option<io::Error>,
+}
+
+impl<W: Write> WriterObserver<W> {
+    /// Wraps `writer`, storing the last error, see [`WriteErrorPolicy::StoreLastError`].
+    pub fn new(writer: W) -> WriterObserver<W> {
+        WriterObserver::with_policy(writer, WriteErrorPolicy::default())
+    }
+    pub fn with_policy(writer: W, policy: WriteErrorPolicy) -> WriterObserver<W> {
+        WriterObserver { writer, policy, last_error: None }
+    }
+    /// The most recent error kept under [`WriteErrorPolicy::StoreLastError`].
+    pub fn last_error(&self) -> Option<&io::Error> {
+        self.last_error.as_ref()
+    }
+    /// Takes the kept error, so that the next one can be told apart.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.last_error.take()
+    }
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+    fn handle(&mut self, result: io::Result<()>) {
+        let Err(error) = result else {
+            return;
+        };
+        match self.policy {
+            WriteErrorPolicy::Panic => panic!("WriterObserver failed to write: {}", error),
+            WriteErrorPolicy::Ignore => {}
+            WriteErrorPolicy::StoreLastError => self.last_error = Some(error),
+        }
+    }
+}
+
+impl<T: Display + ?Sized, W: Write + Send> Observer<T> for WriterObserver<W> {
+    fn notify(&mut self, event: &T) {
+        let result = writeln!(self.writer, "{}", event);
+        self.handle(result);
+    }
+    fn flush(&mut self) {
+        let result = self.writer.flush();
+        self.handle(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::writer::*;
+    use crate::Observable;
+    use std::sync::{Arc, Mutex};
+
+    // accepts `room` bytes, then fails every write
+    struct Full {
+        room: usize,
+    }
+
+    impl Write for Full {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.room == 0 {
+                return Err(io::Error::new(io::ErrorKind::WriteZero, "full"));
+            }
+            let n = buf.len().min(self.room);
+            self.room -= n;
+            Ok(n)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_writer_observer() {
+        let mut observable = Observable::<u32>::new();
+        let log = Arc::new(Mutex::new(WriterObserver::new(Vec::new())));
+        observable.register_concrete(&log);
+
+        assert_eq!(observable.send_to_all(&1), 1);
+        assert_eq!(observable.send_to_all(&22), 1);
+        let log = log.lock().unwrap();
+        assert_eq!(String::from_utf8_lossy(log.get_ref()), "1\n22\n");
+        assert!(log.last_error().is_none());
+    }
+
+    #[test]
+    fn test_writer_error_policies() {
+        let mut stored = WriterObserver::new(Full { room: 2 });
+        Observer::<str>::notify(&mut stored, "a");
+        assert!(stored.last_error().is_none());
+        Observer::<str>::notify(&mut stored, "b");
+        assert_eq!(stored.take_error().map(|e| e.kind()), Some(io::ErrorKind::WriteZero));
+        assert!(stored.last_error().is_none());
+
+        let mut ignored = WriterObserver::with_policy(Full { room: 0 }, WriteErrorPolicy::Ignore);
+        Observer::<str>::notify(&mut ignored, "a");
+        assert!(ignored.last_error().is_none());
+
+        let mut strict = WriterObserver::with_policy(Full { room: 0 }, WriteErrorPolicy::Panic);
+        let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            Observer::<str>::notify(&mut strict, "a");
+        }));
+        assert!(panicked.is_err());
+    }
+}